@@ -0,0 +1,6 @@
+fn main() {
+    async_graphql_reverse::build::Builder::new("input/schema.graphql")
+        .config("input/reverse.toml")
+        .compile()
+        .unwrap();
+}
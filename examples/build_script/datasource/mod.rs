@@ -0,0 +1,15 @@
+use crate::schema::*;
+use async_graphql::*;
+
+pub struct DataSource;
+impl DataSource {
+    pub async fn query_me(&self, _ctx: &Context<'_>, _object: &Query) -> Result<User> {
+        Ok(User {
+            id: ID::from("1"),
+            name: "alice".to_string(),
+        })
+    }
+    pub async fn user_status(&self, _ctx: &Context<'_>, _object: &User) -> Result<Status> {
+        Ok(Status::Registered)
+    }
+}
@@ -0,0 +1,23 @@
+mod datasource;
+pub mod schema {
+    include!(concat!(env!("OUT_DIR"), "/mod.rs"));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use async_graphql::value;
+
+    #[tokio::test]
+    async fn run_generated_schema() {
+        let schema = schema::schema_builder()
+            .data(datasource::DataSource)
+            .finish();
+        let response = schema.execute("{ me { id name status } }").await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(
+            response.data,
+            value!({ "me": { "id": "1", "name": "alice", "status": "REGISTERED" } })
+        );
+    }
+}
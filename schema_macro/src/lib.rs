@@ -0,0 +1,97 @@
+use async_graphql_reverse::{parse_schema_file, Generator, ModuleLayout, RendererConfig};
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::*;
+use std::path::PathBuf;
+use syn::parse::{Parse, ParseStream};
+use syn::*;
+
+/// `schema!("schema.graphql", config = "reverse.toml")`
+struct SchemaInput {
+    schema_path: LitStr,
+    config_path: Option<LitStr>,
+}
+
+impl Parse for SchemaInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let schema_path: LitStr = input.parse()?;
+        let mut config_path = None;
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            let value: LitStr = input.parse()?;
+            if key == "config" {
+                config_path = Some(value);
+            } else {
+                return Err(Error::new(key.span(), format!("unknown option `{}`", key)));
+            }
+        }
+        Ok(Self {
+            schema_path,
+            config_path,
+        })
+    }
+}
+
+/// Paths are relative to the root of the crate calling the macro.
+fn manifest_relative_path(path: &LitStr) -> PathBuf {
+    let root = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".to_string());
+    PathBuf::from(root).join(path.value())
+}
+
+/// Expands a schema file into the items `output_schema` would write, every phase in an inline module.
+/// Paths are relative to the root of the calling crate, which also needs `async-graphql` as a dependency.
+///
+///```ignore
+/// mod schema {
+///     async_graphql_reverse_macro::schema!("schema.graphql", config = "reverse.toml");
+/// }
+///```
+#[proc_macro]
+pub fn schema(input: TokenStream) -> TokenStream {
+    let SchemaInput {
+        schema_path,
+        config_path,
+    } = syn::parse_macro_input!(input);
+
+    match expand_schema(&schema_path, config_path.as_ref()) {
+        Ok(expand) => TokenStream::from(expand),
+        Err(e) => TokenStream::from(Error::new(Span::call_site(), e).to_compile_error()),
+    }
+}
+
+fn expand_schema(
+    schema_path: &LitStr,
+    config_path: Option<&LitStr>,
+) -> std::result::Result<proc_macro2::TokenStream, String> {
+    let schema_file = manifest_relative_path(schema_path).display().to_string();
+    let config_file = config_path.map(|path| manifest_relative_path(path).display().to_string());
+
+    let config = match &config_file {
+        Some(config_file) => RendererConfig::load(config_file).map_err(|e| e.to_string())?,
+        None => RendererConfig::default(),
+    };
+    let structured_schema = parse_schema_file(&schema_file, &config).map_err(|e| e.to_string())?;
+    let files = Generator::new(&structured_schema, &config)
+        .module_layout(ModuleLayout::Inline)
+        .format(false)
+        .schema_files()
+        .map_err(|e| e.to_string())?;
+
+    let items = files
+        .get("mod.rs")
+        .unwrap_or_default()
+        .parse::<proc_macro2::TokenStream>()
+        .map_err(|e| e.to_string())?;
+
+    // recompile when the inputs change
+    let tracked_files = std::iter::once(&schema_file).chain(config_file.iter());
+    Ok(quote! {
+        #( const _: &str = include_str!(#tracked_files); )*
+        #items
+    })
+}
@@ -0,0 +1,53 @@
+use async_graphql::value;
+
+mod datasource {
+    use crate::schema::{Query, UpperDirective};
+    use async_graphql::extensions::ResolveFut;
+    use async_graphql::*;
+
+    pub struct DataSource;
+
+    impl DataSource {
+        pub async fn query_name(&self, _ctx: &Context<'_>, _object: &Query) -> Result<String> {
+            Ok("alice".to_string())
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl CustomDirective for UpperDirective {
+        async fn resolve_field(
+            &self,
+            _ctx: &Context<'_>,
+            resolve: ResolveFut<'_>,
+        ) -> ServerResult<Option<Value>> {
+            let value = resolve.await?;
+            Ok(value.map(|value| match value {
+                Value::String(s) => Value::String(format!(
+                    "{}{}",
+                    self.prefix.clone().unwrap_or_default(),
+                    s.to_uppercase()
+                )),
+                value => value,
+            }))
+        }
+    }
+}
+
+mod schema {
+    async_graphql_reverse_macro::schema!("tests/input/directives.graphql");
+}
+
+#[tokio::test]
+async fn resolve_fields_with_custom_directives() {
+    let schema = schema::schema_builder()
+        .data(datasource::DataSource)
+        .finish();
+    let response = schema
+        .execute(r#"{ name @upper(prefix: "> ") plain: name }"#)
+        .await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(
+        response.data,
+        value!({ "name": "> ALICE", "plain": "alice" })
+    );
+}
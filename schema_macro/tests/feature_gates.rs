@@ -0,0 +1,54 @@
+use async_graphql::{value, EmptyMutation, EmptySubscription, Schema};
+
+mod datasource {
+    use crate::schema::{Query, SearchResult, User};
+    use async_graphql::*;
+
+    pub struct DataSource;
+
+    impl DataSource {
+        pub async fn query_search(
+            &self,
+            _ctx: &Context<'_>,
+            _object: &Query,
+            text: String,
+        ) -> Result<Vec<SearchResult>> {
+            Ok(vec![SearchResult::User(User { name: text })])
+        }
+    }
+}
+
+mod schema {
+    async_graphql_reverse_macro::schema!(
+        "tests/input/feature_gates.graphql",
+        config = "tests/input/feature_gates.toml"
+    );
+}
+
+// the billing feature isn't enabled, so Invoice and what refers to it are left out.
+#[tokio::test]
+async fn leave_out_the_types_of_disabled_features() {
+    let schema = Schema::build(schema::Query {}, EmptyMutation, EmptySubscription)
+        .data(datasource::DataSource)
+        .finish();
+    let response = schema
+        .execute(r#"{ search(text: "rust") { ... on User { name } } }"#)
+        .await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(response.data, value!({ "search": [{ "name": "rust" }] }));
+
+    let response = schema
+        .execute(r#"{ __type(name: "SearchResult") { possibleTypes { name } } invoice: __type(name: "Invoice") { name } }"#)
+        .await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(
+        response.data,
+        value!({
+            "__type": { "possibleTypes": [{ "name": "User" }] },
+            "invoice": null,
+        })
+    );
+
+    let response = schema.execute("{ invoice { total } }").await;
+    assert!(!response.errors.is_empty());
+}
@@ -0,0 +1,95 @@
+use async_graphql::{value, EmptyMutation, EmptySubscription, Schema};
+
+mod blog {
+    use async_graphql::*;
+
+    #[derive(Debug, Clone)]
+    pub struct Post {
+        pub id: ID,
+        pub title: String,
+    }
+
+    #[Object]
+    impl Post {
+        pub async fn id(&self) -> ID {
+            self.id.clone()
+        }
+
+        pub async fn title(&self) -> &str {
+            &self.title
+        }
+    }
+}
+
+mod datasource {
+    use crate::blog::Post;
+    use crate::schema::{Node, Query, SearchResult, User};
+    use async_graphql::*;
+
+    pub struct DataSource;
+
+    impl DataSource {
+        pub async fn query_search(
+            &self,
+            _ctx: &Context<'_>,
+            _object: &Query,
+            text: String,
+        ) -> Result<Vec<SearchResult>> {
+            Ok(vec![
+                SearchResult::User(User {
+                    id: ID::from("1"),
+                    name: text.clone(),
+                }),
+                SearchResult::Post(Post {
+                    id: ID::from("2"),
+                    title: text,
+                }),
+            ])
+        }
+
+        pub async fn query_node(
+            &self,
+            _ctx: &Context<'_>,
+            _object: &Query,
+            id: ID,
+        ) -> Result<Option<Node>> {
+            Ok(Some(Node::Post(Post {
+                id,
+                title: "hello".to_string(),
+            })))
+        }
+    }
+}
+
+mod schema {
+    async_graphql_reverse_macro::schema!(
+        "tests/input/mapped_types.graphql",
+        config = "tests/input/mapped_types.toml"
+    );
+}
+
+#[tokio::test]
+async fn resolve_mapped_union_members_and_implementors() {
+    let schema = Schema::build(schema::Query {}, EmptyMutation, EmptySubscription)
+        .data(datasource::DataSource)
+        .finish();
+    let response = schema
+        .execute(
+            r#"{
+              search(text: "rust") {
+                ... on User { name }
+                ... on Post { title }
+              }
+              node(id: "3") { id ... on Post { title } }
+            }"#,
+        )
+        .await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(
+        response.data,
+        value!({
+            "search": [{ "name": "rust" }, { "title": "rust" }],
+            "node": { "id": "3", "title": "hello" },
+        })
+    );
+}
@@ -0,0 +1,72 @@
+use async_graphql::{value, EmptyMutation, EmptySubscription, Schema};
+use std::collections::HashSet;
+
+mod datasource {
+    use crate::schema::{Count, Email, Payload, Query};
+    use async_graphql::*;
+
+    pub struct DataSource;
+
+    impl DataSource {
+        pub async fn query_check(
+            &self,
+            _ctx: &Context<'_>,
+            _object: &Query,
+            email: Email,
+            count: Count,
+            payload: Payload,
+        ) -> Result<bool> {
+            Ok(email.0.contains('@') && count.0 >= 0 && payload.0.is_object())
+        }
+    }
+}
+
+mod schema {
+    async_graphql_reverse_macro::schema!(
+        "tests/input/scalars.graphql",
+        config = "tests/input/scalars.toml"
+    );
+}
+
+#[tokio::test]
+async fn check_scalar_values() {
+    let schema = Schema::build(schema::Query {}, EmptyMutation, EmptySubscription)
+        .data(datasource::DataSource)
+        .finish();
+    let response = schema
+        .execute(r#"{ check(email: "me@example.com", count: 3, payload: { a: [1] }) }"#)
+        .await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(response.data, value!({ "check": true }));
+
+    let response = schema
+        .execute(r#"{ check(email: "nobody", count: 3, payload: {}) }"#)
+        .await;
+    assert_eq!(response.errors.len(), 1);
+    assert!(response.errors[0].message.contains("does not match"));
+
+    let response = schema
+        .execute(r#"{ check(email: "me@example.com", count: -1, payload: {}) }"#)
+        .await;
+    assert_eq!(response.errors.len(), 1);
+    assert!(response.errors[0].message.contains("is less than 0"));
+
+    let response = schema
+        .execute(r#"{ __type(name: "Email") { specifiedByURL } }"#)
+        .await;
+    assert_eq!(
+        response.data,
+        value!({ "__type": { "specifiedByURL": "https://example.com/email" } })
+    );
+}
+
+#[test]
+fn derive_configured_traits() {
+    let emails: HashSet<schema::Email> = vec![
+        schema::Email("me@example.com".to_string()),
+        schema::Email("me@example.com".to_string()),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(emails.len(), 1);
+}
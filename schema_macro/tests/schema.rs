@@ -0,0 +1,77 @@
+use async_graphql::{value, EmptyMutation, EmptySubscription, Schema};
+
+mod datasource {
+    use crate::schema::{Query, User};
+    use async_graphql::*;
+
+    pub struct DataSource;
+
+    impl DataSource {
+        pub async fn query_me(&self, _ctx: &Context<'_>, _object: &Query) -> Result<User> {
+            Ok(User {
+                id: ID::from("1"),
+                name: "alice".to_string(),
+                friends: vec![],
+            })
+        }
+
+        pub async fn query_greeting(
+            &self,
+            _ctx: &Context<'_>,
+            _object: &Query,
+            name: String,
+        ) -> Result<String> {
+            Ok(format!("hello, {}", name))
+        }
+    }
+}
+
+mod schema {
+    async_graphql_reverse_macro::schema!(
+        "tests/input/schema.graphql",
+        config = "tests/input/reverse.toml"
+    );
+}
+
+#[tokio::test]
+async fn expand_schema_file() {
+    let schema = Schema::build(schema::Query {}, EmptyMutation, EmptySubscription)
+        .data(datasource::DataSource)
+        .finish();
+    let response = schema
+        .execute(r#"{ me { id name friends { id } } hello(name: "bob") }"#)
+        .await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(
+        response.data,
+        value!({
+            "me": { "id": "1", "name": "alice", "friends": [] },
+            "hello": "hello, bob",
+        })
+    );
+}
+
+#[tokio::test]
+async fn merge_graphql_attributes() {
+    let schema = Schema::build(schema::Query {}, EmptyMutation, EmptySubscription)
+        .data(datasource::DataSource)
+        .finish();
+    let response = schema
+        .execute(r#"{ __type(name: "Query") { fields { name } } }"#)
+        .await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(
+        response.data,
+        value!({ "__type": { "fields": [{ "name": "me" }] } })
+    );
+}
+
+#[tokio::test]
+async fn merge_cache_control_into_graphql_attribute() {
+    let schema = Schema::build(schema::Query {}, EmptyMutation, EmptySubscription)
+        .data(datasource::DataSource)
+        .finish();
+    let response = schema.execute(r#"{ hello(name: "bob") }"#).await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(response.cache_control.max_age, 30);
+}
@@ -0,0 +1,52 @@
+use async_graphql::value;
+
+mod datasource {
+    use crate::schema::{Query, User, UserId};
+    use async_graphql::*;
+
+    pub struct DataSource;
+
+    impl DataSource {
+        pub async fn query_user(
+            &self,
+            _ctx: &Context<'_>,
+            _object: &Query,
+            id: UserId,
+        ) -> Result<Option<User>> {
+            Ok(Some(User {
+                id: id.clone(),
+                friend_ids: vec![id],
+            }))
+        }
+    }
+}
+
+mod schema {
+    async_graphql_reverse_macro::schema!(
+        "tests/input/typed_ids.graphql",
+        config = "tests/input/typed_ids.toml"
+    );
+}
+
+#[tokio::test]
+async fn serve_typed_ids_as_id() {
+    let schema = schema::schema_builder()
+        .data(datasource::DataSource)
+        .finish();
+    let response = schema
+        .execute(r#"{ user(id: "7") { id friendIds } }"#)
+        .await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(
+        response.data,
+        value!({ "user": { "id": "7", "friendIds": ["7"] } })
+    );
+
+    let response = schema
+        .execute(r#"{ id: __type(name: "ID") { kind } userId: __type(name: "UserId") { kind } }"#)
+        .await;
+    assert_eq!(
+        response.data,
+        value!({ "id": { "kind": "SCALAR" }, "userId": null })
+    );
+}
@@ -1,18 +1,47 @@
 use async_graphql_reverse::*;
-use clap::{Parser, Subcommand};
+use clap::{ErrorKind, IntoApp, Parser, Subcommand};
 use env_logger;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+const DEFAULT_CONFIG_PATH: &str = "reverse.toml";
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
 
 #[derive(Parser)]
 #[clap(version = "0.6.1", author = "tacogips")]
 struct Opts {
-    #[clap(long, short)]
-    input_schema: String,
+    /// Can be given more than once to parse several files as one schema.
+    #[clap(long, short, required = true)]
+    input_schema: Vec<String>,
 
+    /// With `init`, where the config is written. Defaults to reverse.toml.
     #[clap(long, short)]
     config: Option<String>,
 
+    /// Required except for `init` and `explain`.
+    #[clap(long, short)]
+    output_dir: Option<String>,
+
+    /// Keep running and regenerate when the schema or the config changes.
     #[clap(long, short)]
-    output_dir: String,
+    watch: bool,
+
+    /// Fail when the config does not match the schema instead of warning.
+    #[clap(long)]
+    strict: bool,
+
+    /// Only keep the elements tagged `@tag(name: ..)` with one of these, separated by commas.
+    /// Overrides include_tags of the config.
+    #[clap(long, use_value_delimiter = true)]
+    include_tags: Vec<String>,
+
+    /// Remove the elements tagged with one of these. Overrides exclude_tags of the config.
+    #[clap(long, use_value_delimiter = true)]
+    exclude_tags: Vec<String>,
 
     #[clap(subcommand)]
     command: Command,
@@ -22,11 +51,22 @@ struct Opts {
 enum Command {
     Schema,
     DataSource,
+    /// Write a starter config for the schema.
+    Init {
+        /// Overwrite the config if it exists.
+        #[clap(long)]
+        force: bool,
+    },
+    /// Print how a field is generated and the config entries that touch it.
+    Explain {
+        /// The field, e.g. `User.friends`.
+        target: String,
+    },
 }
 
 fn setup_logger() {
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
-        .target(env_logger::Target::Stdout)
+        .target(env_logger::Target::Stderr)
         .init();
 }
 
@@ -34,49 +74,165 @@ fn main() {
     setup_logger();
     let opts: Opts = Opts::parse();
 
-    let config = match opts.config {
-        Some(config_path) => match RendererConfig::load(&config_path) {
-            Ok(config) => config,
-            Err(e) => {
-                println!("failed to load config toml file:{}", e);
-                std::process::exit(1);
-            }
-        },
-        None => RendererConfig::default(),
+    if opts.watch {
+        if let Command::Init { .. } | Command::Explain { .. } = opts.command {
+            Opts::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "--watch only regenerates the code, it can't be used with init or explain",
+                )
+                .exit();
+        }
+        watch(&opts);
+    } else if let Err(e) = run(&opts) {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn run(opts: &Opts) -> Result<()> {
+    if let Command::Init { force } = opts.command {
+        return init(opts, force);
+    }
+    if let Command::Explain { target } = &opts.command {
+        return explain(opts, target);
+    }
+
+    let output_dir = match &opts.output_dir {
+        Some(output_dir) => output_dir,
+        None => Opts::command()
+            .error(
+                ErrorKind::MissingRequiredArgument,
+                "--output-dir is required to generate the code",
+            )
+            .exit(),
     };
 
+    // the [[additional]] schema bodies live in the config, so reloading it picks them up too.
+    let config = load_config(opts)?;
+    let structured_schema = parse_schema_files(&opts.input_schema, &config)?;
     match opts.command {
-        Command::DataSource => match parse_schema_file(&opts.input_schema, &config) {
-            Ok(structured_schema) => {
-                match output_datasource(&opts.output_dir, structured_schema, &config) {
-                    Ok(()) => {
-                        println!("files outputed in {}", opts.output_dir);
-                    }
-
-                    Err(e) => {
-                        println!("{}", e);
-                    }
-                }
-            }
-            Err(e) => {
-                println!("{}", e);
-            }
-        },
-        Command::Schema => match parse_schema_file(&opts.input_schema, &config) {
-            Ok(structured_schema) => {
-                match output_schema(&opts.output_dir, structured_schema, config) {
-                    Ok(()) => {
-                        println!("files outputed in {}", opts.output_dir);
-                    }
-
-                    Err(e) => {
-                        println!("{}", e);
-                    }
-                }
-            }
-            Err(e) => {
-                println!("{}", e);
+        Command::DataSource => output_datasource(output_dir, structured_schema, &config)?,
+        Command::Schema => output_schema(output_dir, structured_schema, config)?,
+        Command::Init { .. } | Command::Explain { .. } => unreachable!(),
+    }
+    println!("files outputed in {}", output_dir);
+    Ok(())
+}
+
+fn load_config(opts: &Opts) -> Result<RendererConfig> {
+    let mut config = match &opts.config {
+        Some(config_path) => RendererConfig::load(config_path)?,
+        None => RendererConfig::default(),
+    };
+    config.strict |= opts.strict;
+    if !opts.include_tags.is_empty() {
+        config.include_tags = opts.include_tags.clone();
+    }
+    if !opts.exclude_tags.is_empty() {
+        config.exclude_tags = opts.exclude_tags.clone();
+    }
+    Ok(config)
+}
+
+fn explain(opts: &Opts, target: &str) -> Result<()> {
+    let config = load_config(opts)?;
+    let (parsed, filtered) = parse_and_filter_schema_files(&opts.input_schema, &config)?;
+    print!("{}", explain_field(&parsed, &filtered, &config, target)?);
+    Ok(())
+}
+
+fn init(opts: &Opts, force: bool) -> Result<()> {
+    let config_path = opts.config.as_deref().unwrap_or(DEFAULT_CONFIG_PATH);
+    if !force && Path::new(config_path).exists() {
+        return Err(Error::file(
+            config_path,
+            io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                "the config already exists, use --force to overwrite it",
+            ),
+        ));
+    }
+
+    let structured_schema = parse_schema_files(&opts.input_schema, &RendererConfig::default())?;
+    let template = config_template(&structured_schema)?;
+    fs::write(config_path, template).map_err(|e| Error::file(config_path, e))?;
+    println!("config written to {}", config_path);
+    Ok(())
+}
+
+/// Run, then run everything again whenever one of the inputs changes. Errors are reported without exiting.
+fn watch(opts: &Opts) {
+    let watched_files: Vec<&str> = opts
+        .input_schema
+        .iter()
+        .chain(opts.config.iter())
+        .map(String::as_str)
+        .collect();
+
+    let mut last_modified = modified_times(&watched_files);
+    loop {
+        if let Err(e) = run(opts) {
+            eprintln!("error: {}", e);
+        }
+        println!("watching for changes...");
+
+        loop {
+            thread::sleep(WATCH_INTERVAL);
+            if update_modified_times(&watched_files, &mut last_modified) {
+                break;
             }
-        },
+        }
+    }
+}
+
+/// The modification time of each file, `None` for the files that can't be read.
+fn modified_times(files: &[&str]) -> HashMap<String, Option<SystemTime>> {
+    files
+        .iter()
+        .map(|file| {
+            let modified = fs::metadata(file).and_then(|meta| meta.modified()).ok();
+            (file.to_string(), modified)
+        })
+        .collect()
+}
+
+/// Whether one of the files was modified, created or removed since `last_modified`, which is updated.
+fn update_modified_times(
+    files: &[&str],
+    last_modified: &mut HashMap<String, Option<SystemTime>>,
+) -> bool {
+    let modified = modified_times(files);
+    if modified == *last_modified {
+        return false;
+    }
+    *last_modified = modified;
+    true
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    pub fn detect_modified_files() {
+        let path = std::env::temp_dir().join("detect_modified_files.graphql");
+        fs::write(&path, "type Query { active: Boolean! }").unwrap();
+        let files = [path.to_str().unwrap()];
+
+        let mut last_modified = modified_times(&files);
+        assert!(!update_modified_times(&files, &mut last_modified));
+
+        thread::sleep(Duration::from_millis(20));
+        fs::write(&path, "type Query { active: Boolean }").unwrap();
+        assert!(update_modified_times(&files, &mut last_modified));
+        let modified = fs::metadata(&path).unwrap().modified().unwrap();
+        assert_eq!(last_modified[files[0]], Some(modified));
+        assert!(!update_modified_times(&files, &mut last_modified));
+
+        fs::remove_file(&path).unwrap();
+        assert!(update_modified_times(&files, &mut last_modified));
+        assert_eq!(last_modified[files[0]], None);
     }
 }
@@ -0,0 +1,164 @@
+//! Generate the schema from a build script.
+//!
+//!```ignore
+//! // build.rs
+//! fn main() {
+//!     async_graphql_reverse::build::Builder::new("schema.graphql")
+//!         .config("reverse.toml")
+//!         .compile()
+//!         .unwrap();
+//! }
+//!
+//! // src/lib.rs
+//! mod schema {
+//!     include!(concat!(env!("OUT_DIR"), "/mod.rs"));
+//! }
+//!```
+//!
+//! Each phase is included into its own inline module of the including module, so the `super::` imports
+//! between phases resolve the same as with the CLI output. The `DataSource` is still imported by the
+//! config `header`, from `[module_path] data_source` (`crate::datasource::DataSource` by default).
+
+use crate::config::RendererConfig;
+use crate::error::{Error, Result};
+use crate::parse::parse_schema_files;
+use crate::render::{setup_output_dir, Generator, ModuleLayout};
+use std::env;
+use std::path::{Path, PathBuf};
+
+pub struct Builder {
+    schema_paths: Vec<PathBuf>,
+    config_path: Option<PathBuf>,
+    out_dir: Option<PathBuf>,
+}
+
+impl Builder {
+    pub fn new<P: AsRef<Path>>(schema_path: P) -> Self {
+        Self {
+            schema_paths: vec![schema_path.as_ref().to_path_buf()],
+            config_path: None,
+            out_dir: None,
+        }
+    }
+
+    /// Add another schema file. All the files are parsed as one schema.
+    pub fn schema<P: AsRef<Path>>(mut self, schema_path: P) -> Self {
+        self.schema_paths.push(schema_path.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn config<P: AsRef<Path>>(mut self, config_path: P) -> Self {
+        self.config_path = Some(config_path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Defaults to `OUT_DIR`.
+    pub fn out_dir<P: AsRef<Path>>(mut self, out_dir: P) -> Self {
+        self.out_dir = Some(out_dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// The `cargo:rerun-if-changed` lines of the schema files and the config.
+    fn rerun_if_changed(&self) -> Vec<String> {
+        self.schema_paths
+            .iter()
+            .chain(self.config_path.iter())
+            .map(|path| format!("cargo:rerun-if-changed={}", path.display()))
+            .collect()
+    }
+
+    /// Generate the schema files and the `mod.rs` to `include!`.
+    pub fn compile(self) -> Result<()> {
+        for line in self.rerun_if_changed() {
+            println!("{}", line);
+        }
+
+        let config = match &self.config_path {
+            Some(config_path) => RendererConfig::load(&config_path.display().to_string())?,
+            None => RendererConfig::default(),
+        };
+
+        let out_dir = match self.out_dir {
+            Some(out_dir) => out_dir,
+            None => env::var_os("OUT_DIR")
+                .map(PathBuf::from)
+                .ok_or_else(|| Error::EnvNotSet("OUT_DIR".to_string()))?,
+        };
+        let out_dir = if out_dir.is_absolute() {
+            out_dir
+        } else {
+            env::current_dir()?.join(out_dir)
+        };
+        let out_dir_str = out_dir.display().to_string();
+
+        let structured_schema = parse_schema_files(&self.schema_paths, &config)?;
+        setup_output_dir(&out_dir_str)?;
+        Generator::new(&structured_schema, &config)
+            .module_layout(ModuleLayout::Include(out_dir))
+            .schema_files()?
+            .write_to(&out_dir_str)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use std::fs;
+
+    #[test]
+    pub fn compile_into_out_dir() {
+        let dir = env::temp_dir().join("compile_into_out_dir");
+        let schema_path = dir.join("schema.graphql");
+        let config_path = dir.join("reverse.toml");
+        let out_dir = dir.join("out");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            &schema_path,
+            "schema { query: Query } type Query { status: Status! } enum Status { REGISTERED }",
+        )
+        .unwrap();
+        fs::write(&config_path, "").unwrap();
+
+        let builder = Builder::new(&schema_path)
+            .config(&config_path)
+            .out_dir(&out_dir);
+        assert_eq!(
+            builder.rerun_if_changed(),
+            vec![
+                format!("cargo:rerun-if-changed={}", schema_path.display()),
+                format!("cargo:rerun-if-changed={}", config_path.display()),
+            ]
+        );
+        builder.compile().unwrap();
+
+        // each phase module of the mod.rs includes the file of the phase by its absolute path.
+        let mod_source = fs::read_to_string(out_dir.join("mod.rs")).unwrap();
+        let included: Vec<(String, PathBuf)> = syn::parse_file(&mod_source)
+            .unwrap()
+            .items
+            .into_iter()
+            .filter_map(|item| match item {
+                syn::Item::Mod(module) => {
+                    let (_, items) = module.content?;
+                    match items.first()? {
+                        syn::Item::Macro(item) if item.mac.path.is_ident("include") => {
+                            let path = item.mac.parse_body::<syn::LitStr>().unwrap().value();
+                            Some((module.ident.to_string(), PathBuf::from(path)))
+                        }
+                        _ => None,
+                    }
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            included,
+            vec![
+                ("objects".to_string(), out_dir.join("objects.rs")),
+                ("enums".to_string(), out_dir.join("enums.rs")),
+            ]
+        );
+        assert!(included.iter().all(|(_, path)| path.is_file()));
+    }
+}
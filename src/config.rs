@@ -1,67 +1,574 @@
-use anyhow::{anyhow, Result};
-use serde::Deserialize;
+use crate::error::{Error, Result};
+use crate::parse::{find_directive, Definitions, Directive, Field, PrimitiveKind, TypeDef};
+use regex::Regex;
+use serde::{Deserialize, Deserializer};
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::fs;
+use std::ops::Deref;
 use toml;
 
+/// A value of the config with where it is written in the config file, recorded with `toml::Spanned`
+/// so the errors of the rust snippets can point at their line. Values not read from toml have no span.
+#[derive(Clone, Default)]
+pub struct Located<T> {
+    value: T,
+    span: Option<(usize, usize)>,
+}
+
+impl<T> Located<T> {
+    pub fn new(value: T) -> Self {
+        Self { value, span: None }
+    }
+
+    /// The byte range of the value in the config file.
+    pub fn span(&self) -> Option<(usize, usize)> {
+        self.span
+    }
+}
+
+impl<T> Deref for Located<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> From<T> for Located<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: PartialEq> PartialEq for Located<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Located<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Located<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Located<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let spanned = toml::Spanned::<T>::deserialize(deserializer)?;
+        Ok(Self {
+            span: Some(spanned.span()),
+            value: spanned.into_inner(),
+        })
+    }
+}
+
 pub struct CustomResolvers {
+    pub entry_name: String,
     pub using: Vec<String>,
     pub bodies: Vec<String>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct AdditionalResolver {
     pub target_type: String,
-    pub body: String,
-    pub using: Option<String>,
+    pub body: Located<String>,
+    pub using: Option<Located<String>>,
+}
+
+impl AdditionalResolver {
+    pub fn entry_name(&self) -> String {
+        format!(
+            "[[additional_resolver]] target_type = {:?}",
+            self.target_type
+        )
+    }
 }
 
 pub struct HiddenFields {
+    pub entry_name: String,
     pub using: Vec<String>,
     pub field_defs: Vec<String>,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Clone, Debug, PartialEq)]
 pub struct EnumSetting {
     pub target_enum: String,
     pub rename_items: Option<String>,
     pub value: Option<Vec<EnumValueSetting>>,
+    /// Written in the schema with `@reverse` rather than in the config file.
+    #[serde(skip)]
+    pub inline: bool,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+impl EnumSetting {
+    pub fn entry_name(&self) -> String {
+        if self.inline {
+            format!("@{} of {}", REVERSE_DIRECTIVE, self.target_enum)
+        } else {
+            format!("[[enum]] target_enum = {:?}", self.target_enum)
+        }
+    }
+
+    pub fn value_entry_name(&self, value: &EnumValueSetting) -> String {
+        if self.inline {
+            format!(
+                "@{} of {}.{}",
+                REVERSE_DIRECTIVE, self.target_enum, value.value
+            )
+        } else {
+            format!("{}, value = {:?}", self.entry_name(), value.value)
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
 pub struct EnumValueSetting {
     pub value: String,
     pub rename: Option<String>,
+    pub visible: Option<Located<Visible>>,
 }
 
-#[derive(Deserialize, Debug)]
-pub struct HiddenField {
+/// `visible` of async-graphql. `false` hides the element from introspection, a function name
+/// decides it per request with `fn(&Context<'_>) -> bool`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Visible {
+    Bool(bool),
+    Function(String),
+}
+
+/// The directive that hides an element like `visible = false`.
+pub const INTERNAL_DIRECTIVE: &str = "internal";
+/// The directive that marks an argument or input field `secret`.
+pub const SENSITIVE_DIRECTIVE: &str = "sensitive";
+/// The directive `include_tags` and `exclude_tags` filter the schema by, `@tag(name: "..")`.
+pub const TAG_DIRECTIVE: &str = "tag";
+/// The cache hints of an object or a field, `@cacheControl(maxAge: 60, scope: PRIVATE)`.
+pub const CACHE_CONTROL_DIRECTIVE: &str = "cacheControl";
+/// The settings of the generator written in the schema, e.g. `@reverse(resolver: "field")`.
+/// See `parse::inline_settings`.
+pub const REVERSE_DIRECTIVE: &str = "reverse";
+
+/// Settings of an object, input object, enum, interface, union or scalar.
+#[derive(Deserialize, Debug, Clone)]
+pub struct TypeSetting {
     pub target_type: String,
-    pub field_def: String,
-    pub using: Option<String>,
+    pub visible: Option<Located<Visible>>,
+    /// Gate the type with `#[cfg(feature = "..")]`, along with everything that refers to it.
+    pub feature: Option<String>,
+}
+
+impl TypeSetting {
+    pub fn entry_name(&self) -> String {
+        format!("[[type]] target_type = {:?}", self.target_type)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ScalarSetting {
+    pub target_scalar: String,
+    /// One of `String` (default), `i64`, `f64`, `bool` or `serde_json::Value`.
+    pub backing_type: Option<String>,
+    /// A regex the value must match. For `String` backed scalars.
+    pub pattern: Option<String>,
+    /// The inclusive range of the value. For `i64` and `f64` backed scalars.
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    /// Names added to `#[derive(..)]` of the newtype, e.g. `"PartialEq, Eq, Hash"`.
+    pub derives: Option<Located<String>>,
+    /// Overrides the url of `@specifiedBy`.
+    pub specified_by_url: Option<String>,
+}
+
+impl ScalarSetting {
+    pub fn entry_name(&self) -> String {
+        format!("[[scalar]] target_scalar = {:?}", self.target_scalar)
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
+pub struct HiddenField {
+    pub target_type: String,
+    pub field_def: Located<String>,
+    pub using: Option<Located<String>>,
+}
+
+impl HiddenField {
+    pub fn entry_name(&self) -> String {
+        format!(
+            "[[hidden_field]] target_type = {:?}, field_def = {:?}",
+            self.target_type, *self.field_def
+        )
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
 pub struct ResolverArgument {
     pub arg_name: String,
     pub arg_type: String,
     pub arg_description: Option<String>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Debug, Clone, PartialEq)]
 pub struct ResolverSetting {
     pub target_type: String,
     pub target_field: String,
     pub resolver_type: Option<String>,
-    pub attribute: Option<String>,
+    pub attribute: Option<Located<String>>,
     pub argument: Option<Vec<ResolverArgument>>,
+    /// Written in the schema with `@reverse` rather than in the config file.
+    #[serde(skip)]
+    pub inline: bool,
+}
+
+impl ResolverSetting {
+    pub fn entry_name(&self) -> String {
+        if self.inline {
+            format!(
+                "@{} of {}.{}",
+                REVERSE_DIRECTIVE, self.target_type, self.target_field
+            )
+        } else {
+            format!(
+                "[[resolver]] target_type = {:?}, target_field = {:?}",
+                self.target_type, self.target_field
+            )
+        }
+    }
+}
+
+/// A glob like `*Connection`, matched against the whole name, or a regular expression between
+/// slashes like `/^(User|Post)$/`.
+#[derive(Debug, Clone)]
+pub struct NamePattern {
+    pattern: String,
+    regex: Regex,
+}
+
+impl NamePattern {
+    pub fn new(pattern: &str) -> std::result::Result<Self, regex::Error> {
+        let regex = match pattern
+            .strip_prefix('/')
+            .and_then(|pattern| pattern.strip_suffix('/'))
+        {
+            Some(regex) => Regex::new(regex)?,
+            None => {
+                let glob = regex::escape(pattern)
+                    .replace("\\*", ".*")
+                    .replace("\\?", ".");
+                Regex::new(&format!("^{}$", glob))?
+            }
+        };
+        Ok(Self {
+            pattern: pattern.to_string(),
+            regex,
+        })
+    }
+
+    pub fn is_match(&self, name: &str) -> bool {
+        self.regex.is_match(name)
+    }
 }
 
+impl fmt::Display for NamePattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.pattern)
+    }
+}
+
+impl<'de> Deserialize<'de> for NamePattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let pattern = String::deserialize(deserializer)?;
+        NamePattern::new(&pattern).map_err(serde::de::Error::custom)
+    }
+}
+
+/// The kind of the type a field returns, or of its elements for a list.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReturnKind {
+    Primitive,
+    /// Also the types replaced by `[[type_mapping]]`.
+    Scalar,
+    Enum,
+    Object,
+    Interface,
+    Union,
+}
+
+impl ReturnKind {
+    fn of(type_def: &TypeDef) -> Option<ReturnKind> {
+        match type_def {
+            TypeDef::Primitive(_) => Some(ReturnKind::Primitive),
+            TypeDef::Scalar(_) | TypeDef::Mapped(_) | TypeDef::AsyncGraphqlPreserved(_) => {
+                Some(ReturnKind::Scalar)
+            }
+            TypeDef::Enum(_) => Some(ReturnKind::Enum),
+            TypeDef::Object(_) => Some(ReturnKind::Object),
+            TypeDef::Interface(_) => Some(ReturnKind::Interface),
+            TypeDef::Union(_) => Some(ReturnKind::Union),
+            TypeDef::InputObject(_) | TypeDef::Directive(_) => None,
+        }
+    }
+}
+
+/// Sets `resolver_type` and `attribute` of the fields of objects it matches. The rules are tried in
+/// order, and each of the two values comes from the first matching rule that sets it. A rule with
+/// only `parent_type` is a default for the fields of those types. `[[resolver]]` takes precedence.
 #[derive(Deserialize, Debug, Clone)]
+pub struct ResolverRule {
+    /// The object the field belongs to.
+    pub parent_type: Option<NamePattern>,
+    pub field: Option<NamePattern>,
+    pub return_kind: Option<ReturnKind>,
+    /// Whether the field returns a list.
+    pub list: Option<bool>,
+    /// Whether the field is nullable. For a list, the list itself.
+    pub nullable: Option<bool>,
+    pub has_arguments: Option<bool>,
+    /// A directive applied to the field, without `@`.
+    pub directive: Option<String>,
+    pub resolver_type: Option<String>,
+    pub attribute: Option<Located<String>>,
+}
+
+impl ResolverRule {
+    /// The matching keys of the rule, e.g. `[[resolver_rule]] parent_type = "*Connection", list = true`.
+    pub fn entry_name(&self) -> String {
+        let mut keys = Vec::<String>::new();
+        if let Some(parent_type) = &self.parent_type {
+            keys.push(format!("parent_type = {}", parent_type));
+        }
+        if let Some(field) = &self.field {
+            keys.push(format!("field = {}", field));
+        }
+        if let Some(return_kind) = &self.return_kind {
+            keys.push(format!("return_kind = {:?}", return_kind));
+        }
+        if let Some(list) = self.list {
+            keys.push(format!("list = {}", list));
+        }
+        if let Some(nullable) = self.nullable {
+            keys.push(format!("nullable = {}", nullable));
+        }
+        if let Some(has_arguments) = self.has_arguments {
+            keys.push(format!("has_arguments = {}", has_arguments));
+        }
+        if let Some(directive) = &self.directive {
+            keys.push(format!("directive = {:?}", directive));
+        }
+        if keys.is_empty() {
+            "[[resolver_rule]]".to_string()
+        } else {
+            format!("[[resolver_rule]] {}", keys.join(", "))
+        }
+    }
+
+    pub fn matches(
+        &self,
+        parent_name: &str,
+        field: &Field,
+        definitions: &Definitions,
+    ) -> Result<bool> {
+        if let Some(parent_type) = &self.parent_type {
+            if !parent_type.is_match(parent_name) {
+                return Ok(false);
+            }
+        }
+        if let Some(field_pattern) = &self.field {
+            if !field_pattern.is_match(&field.name) {
+                return Ok(false);
+            }
+        }
+        if let Some(list) = self.list {
+            if list != field.typ.is_list() {
+                return Ok(false);
+            }
+        }
+        if let Some(nullable) = self.nullable {
+            if nullable != field.typ.nullable() {
+                return Ok(false);
+            }
+        }
+        if let Some(has_arguments) = self.has_arguments {
+            if has_arguments == field.arguments.is_empty() {
+                return Ok(false);
+            }
+        }
+        if let Some(directive) = &self.directive {
+            if find_directive(&field.directives, directive).is_none() {
+                return Ok(false);
+            }
+        }
+        if let Some(return_kind) = self.return_kind {
+            let type_def = field.typ.element_value_type_def(definitions)?;
+            if ReturnKind::of(&type_def) != Some(return_kind) {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
 pub struct FieldSetting {
     pub target_type: String,
     pub target_field: String,
     pub replace_field_type: Option<String>,
+    /// The rust type of an `Int`, `Float` or `ID` field, overriding `[primitive]`.
+    pub primitive_type: Option<Located<String>>,
+    /// The object whose typed ID the `ID` field holds, e.g. `"User"` for `UserId`.
+    pub id_of: Option<String>,
+    /// The objects whose typed IDs the `ID` arguments of the field hold, keyed by argument name.
+    pub argument_id_of: Option<HashMap<String, String>>,
+    /// Render the nullable input field, or the nullable arguments of the field, as `MaybeUndefined<T>`.
+    /// Overrides `maybe_undefined` and `maybe_undefined_types`.
+    pub maybe_undefined: Option<bool>,
+    /// Overrides `@internal` of the field.
+    pub visible: Option<Located<Visible>>,
+    /// Mask the value of the input field in logs. Overrides `@sensitive`.
+    pub secret: Option<bool>,
+    /// The arguments of the field to mask in logs.
+    pub secret_arguments: Option<Vec<String>>,
+    /// Written in the schema with `@reverse` rather than in the config file.
+    #[serde(skip)]
+    pub inline: bool,
+}
+
+impl FieldSetting {
+    pub fn entry_name(&self) -> String {
+        if self.inline {
+            format!(
+                "@{} of {}.{}",
+                REVERSE_DIRECTIVE, self.target_type, self.target_field
+            )
+        } else {
+            format!(
+                "[[field]] target_type = {:?}, target_field = {:?}",
+                self.target_type, self.target_field
+            )
+        }
+    }
+}
+
+/// The `@reverse` settings of the schema. They apply like the entries of the config file, which
+/// replace them for the same field or enum.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InlineSettings {
+    pub resolver: Vec<ResolverSetting>,
+    pub field: Vec<FieldSetting>,
+    pub r#enum: Vec<EnumSetting>,
+}
+
+impl InlineSettings {
+    pub fn is_empty(&self) -> bool {
+        self.resolver.is_empty() && self.field.is_empty() && self.r#enum.is_empty()
+    }
+}
+
+/// Use an existing rust type for a schema type instead of generating it.
+#[derive(Deserialize, Debug, Clone)]
+pub struct TypeMapping {
+    pub graphql_type: String,
+    pub rust_type: Located<String>,
+    /// Imports `rust_type` in the files that use it, for a `rust_type` that is not a full path.
+    pub using: Option<Located<String>>,
+    /// The `scalar_presets` name the mapping comes from.
+    #[serde(skip)]
+    pub preset: Option<String>,
+}
+
+impl TypeMapping {
+    pub fn entry_name(&self) -> String {
+        match &self.preset {
+            Some(preset) => format!("scalar_presets = {:?}", preset),
+            None => format!("[[type_mapping]] graphql_type = {:?}", self.graphql_type),
+        }
+    }
+}
+
+/// Render a directive of the schema as attributes of the items generated for the elements it is
+/// applied to. `{arg}` in the templates is replaced with the value of the argument `arg` of the
+/// applied directive, written as in the schema, and `{{` and `}}` with the braces themselves.
+#[derive(Deserialize, Debug, Clone)]
+pub struct DirectiveMapping {
+    pub directive: String,
+    /// Put on the struct, enum, field member, resolver method, argument or enum variant.
+    pub attribute: Option<String>,
+    /// Put on the datasource stub of the fields the directive is applied to.
+    pub datasource_attribute: Option<String>,
+}
+
+impl DirectiveMapping {
+    pub fn entry_name(&self) -> String {
+        format!("[[directive_mapping]] directive = {:?}", self.directive)
+    }
+
+    pub fn interpolate(&self, template: &str, directive: &Directive) -> Result<String> {
+        let mut result = String::new();
+        let mut chars = template.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    result.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    result.push('}');
+                }
+                '{' => {
+                    let name: String = chars.by_ref().take_while(|c| *c != '}').collect();
+                    let value = directive.argument(name.trim()).ok_or_else(|| {
+                        Error::config_entry(
+                            self.entry_name(),
+                            format!("@{} has no argument {} here", directive.name, name.trim()),
+                        )
+                    })?;
+                    result.push_str(&value.to_string());
+                }
+                c => result.push(c),
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// The scalars async-graphql implements for the types of other crates, by the name of the
+/// async-graphql feature that enables them: `(preset, graphql_type, rust_type)`.
+pub const SCALAR_PRESETS: &[(&str, &str, &str)] = &[
+    ("chrono", "DateTime", "chrono::DateTime<chrono::Utc>"),
+    ("chrono", "NaiveDate", "chrono::NaiveDate"),
+    ("chrono", "NaiveTime", "chrono::NaiveTime"),
+    ("chrono", "NaiveDateTime", "chrono::NaiveDateTime"),
+    ("chrono-duration", "Duration", "chrono::Duration"),
+    ("chrono-tz", "TimeZone", "chrono_tz::Tz"),
+    ("time", "DateTime", "time::OffsetDateTime"),
+    ("time", "LocalDateTime", "time::PrimitiveDateTime"),
+    ("time", "Date", "time::Date"),
+    ("uuid", "UUID", "uuid::Uuid"),
+    ("url", "Url", "url::Url"),
+    ("json", "JSON", "async_graphql::Json<serde_json::Value>"),
+    ("decimal", "Decimal", "rust_decimal::Decimal"),
+    ("bigdecimal", "BigDecimal", "bigdecimal::BigDecimal"),
+];
+
+/// The names `scalar_presets` accepts.
+pub fn scalar_preset_names() -> Vec<&'static str> {
+    let mut names = SCALAR_PRESETS
+        .iter()
+        .map(|(name, _, _)| *name)
+        .collect::<Vec<&str>>();
+    names.dedup();
+    names
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -110,7 +617,7 @@ pub type FieldsResolverSetting<'a> = HashMap<DefinedFieldName, &'a ResolverSetti
 pub type FieldsSetting<'a> = HashMap<DefinedFieldName, &'a FieldSetting>;
 pub type EnumFieldsSetting<'a> = HashMap<DefinedEnumName, &'a FieldSetting>;
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Deserialize, Debug, PartialEq, Clone, Copy)]
 #[serde(rename_all = "snake_case")]
 pub enum Phase {
     Objects,
@@ -119,26 +626,180 @@ pub enum Phase {
     Scalars,
     Interfaces,
     Enums,
+    Directives,
+}
+
+impl Phase {
+    pub fn all() -> [Phase; 7] {
+        [
+            Phase::Objects,
+            Phase::InputObjects,
+            Phase::Unions,
+            Phase::Scalars,
+            Phase::Interfaces,
+            Phase::Enums,
+            Phase::Directives,
+        ]
+    }
+
+    /// The name of the module the phase generates.
+    pub fn module_name(&self) -> &'static str {
+        match self {
+            Phase::Objects => "objects",
+            Phase::InputObjects => "input_objects",
+            Phase::Unions => "unions",
+            Phase::Scalars => "scalars",
+            Phase::Interfaces => "interfaces",
+            Phase::Enums => "enums",
+            Phase::Directives => "directives",
+        }
+    }
+
+    /// The name of the file the phase generates, relative to the output directory.
+    pub fn file_name(&self) -> String {
+        format!("{}.rs", self.module_name())
+    }
+}
+
+/// The `[using]` key whose declarations are added to every generated module.
+pub const USING_ALL: &str = "all";
+/// The `[using]` key of the module the `data-source` command generates.
+pub const USING_DATASOURCE: &str = "datasource";
+/// The former name of `USING_DATASOURCE`, still accepted.
+pub const USING_DATASOURCE_ALIAS: &str = "data_source";
+
+/// Where the generated code imports the types of each category and the `DataSource` from.
+/// A category can be moved out of the output, e.g. to a shared crate, by disabling its phase and
+/// pointing its path at the new place.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct ModulePath {
+    pub objects: Located<String>,
+    pub input_objects: Located<String>,
+    pub unions: Located<String>,
+    pub scalars: Located<String>,
+    pub interfaces: Located<String>,
+    pub enums: Located<String>,
+    pub directives: Located<String>,
+    /// The path of the `DataSource` type itself, imported by the default `header`.
+    pub data_source: Located<String>,
+}
+
+impl Default for ModulePath {
+    fn default() -> Self {
+        Self {
+            objects: "super::objects".to_string().into(),
+            input_objects: "super::input_objects".to_string().into(),
+            unions: "super::unions".to_string().into(),
+            scalars: "super::scalars".to_string().into(),
+            interfaces: "super::interfaces".to_string().into(),
+            enums: "super::enums".to_string().into(),
+            directives: "super::directives".to_string().into(),
+            data_source: "crate::datasource::DataSource".to_string().into(),
+        }
+    }
+}
+
+impl ModulePath {
+    /// The module the types of the phase are imported from.
+    pub fn of(&self, phase: &Phase) -> &Located<String> {
+        match phase {
+            Phase::Objects => &self.objects,
+            Phase::InputObjects => &self.input_objects,
+            Phase::Unions => &self.unions,
+            Phase::Scalars => &self.scalars,
+            Phase::Interfaces => &self.interfaces,
+            Phase::Enums => &self.enums,
+            Phase::Directives => &self.directives,
+        }
+    }
+}
+
+/// The rust types the `Int`, `Float` and `ID` primitives are rendered as. Defaults to `i64`, `f64`
+/// and async-graphql's `ID`.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct PrimitiveSetting {
+    pub int: Option<Located<String>>,
+    pub float: Option<Located<String>>,
+    pub id: Option<Located<String>>,
+}
+
+impl PrimitiveSetting {
+    pub fn get(&self, kind: &PrimitiveKind) -> Option<&Located<String>> {
+        match kind {
+            PrimitiveKind::Int => self.int.as_ref(),
+            PrimitiveKind::Float => self.float.as_ref(),
+            PrimitiveKind::ID => self.id.as_ref(),
+            PrimitiveKind::Str | PrimitiveKind::Boolean => None,
+        }
+    }
+
+    pub fn entry_name(kind: &PrimitiveKind) -> String {
+        format!("[primitive] {}", kind.as_ref().to_lowercase())
+    }
 }
 
-#[derive(Deserialize, Default, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct RendererConfig {
-    pub using: Option<HashMap<String, String>>,
+    /// Use declarations added to the generated modules, keyed by the module name of a phase,
+    /// `all` or `datasource`. A value can hold several declarations separated by `;`.
+    pub using: Option<HashMap<String, Located<String>>>,
     #[serde(default = "RendererConfig::default_data_source_fetch_method_from_ctx")]
-    pub data_source_fetch_method: String,
+    pub data_source_fetch_method: Located<String>,
     pub custom_member_types: Option<Vec<String>>,
     pub resolver: Option<Vec<ResolverSetting>>,
+    pub resolver_rule: Option<Vec<ResolverRule>>,
     pub additional_resolver: Option<Vec<AdditionalResolver>>,
     pub hidden_field: Option<Vec<HiddenField>>,
     pub additional: Option<Vec<Additional>>,
     pub ignore: Option<Ignore>,
     pub r#enum: Option<Vec<EnumSetting>>,
+    pub scalar: Option<Vec<ScalarSetting>>,
     pub field: Option<Vec<FieldSetting>>,
+    pub r#type: Option<Vec<TypeSetting>>,
+    pub type_mapping: Option<Vec<TypeMapping>>,
+    pub directive_mapping: Option<Vec<DirectiveMapping>>,
+
+    /// Map the well-known scalars to the types async-graphql implements them for, e.g. `["chrono", "uuid"]`.
+    /// See `SCALAR_PRESETS`. `[[type_mapping]]` takes precedence.
+    #[serde(default)]
+    pub scalar_presets: Vec<String>,
     pub enum_rename_items: Option<String>,
 
     /// With this you can override the header included at the top of the file.
-    #[serde(default = "RendererConfig::default_header")]
-    pub header: String,
+    /// Defaults to importing `async_graphql::*` and `module_path.data_source`. See `header()`.
+    pub header: Option<Located<String>>,
+
+    #[serde(default)]
+    pub module_path: ModulePath,
+
+    /// Generate a `UserId(ID)` newtype for the `id` field of every object, used by the fields and
+    /// arguments tied to the object by name or by `[[field]] id_of`. See `parse::typed_ids`.
+    #[serde(default)]
+    pub typed_ids: bool,
+
+    /// Render nullable input fields and arguments as `MaybeUndefined<T>` instead of `Option<T>`,
+    /// to tell an omitted value from an explicit null.
+    #[serde(default)]
+    pub maybe_undefined: bool,
+
+    /// `maybe_undefined` for the fields of these input objects and the arguments of these objects.
+    #[serde(default)]
+    pub maybe_undefined_types: Vec<String>,
+
+    /// Only keep the fields, input fields and enum values tagged `@tag(name: "..")` with one of these
+    /// names, or whose type is. Types left without fields are removed. See `parse::ignoring::tags`.
+    #[serde(default)]
+    pub include_tags: Vec<String>,
+
+    /// Remove the types, fields, arguments, input fields and enum values tagged with one of these names.
+    /// Takes precedence over `include_tags`.
+    #[serde(default)]
+    pub exclude_tags: Vec<String>,
+
+    /// The rust types of `Int`, `Float` and `ID`. `[[field]] primitive_type` overrides it per field.
+    #[serde(default)]
+    pub primitive: PrimitiveSetting,
 
     /// Rather than determining the resolver type based on the type of the field in the
     /// object, we will use this type instead. Overrides specified in
@@ -146,7 +807,7 @@ pub struct RendererConfig {
     pub resolver_type: Option<String>,
 
     /// Additional attributes to apply to the generated object types.
-    pub additional_attributes: Option<String>,
+    pub additional_attributes: Option<Located<String>>,
 
     /// By default all generation phases are executed. If set, only the specified phases
     /// will be executed.
@@ -161,15 +822,143 @@ pub struct RendererConfig {
     /// you have the Scalars phase disabled because you are using your own scalar types.
     #[serde(default)]
     pub no_dependency_imports: bool,
+
+    /// If set, the problems found by validating the config against the schema are errors
+    /// instead of warnings.
+    #[serde(default)]
+    pub strict: bool,
+
+    /// Keys of the config file that are not known, e.g. `resolver.0.resolver_typ`.
+    #[serde(skip)]
+    pub unknown_keys: Vec<String>,
+
+    /// The path of the loaded config file, named in the errors of its entries.
+    #[serde(skip)]
+    pub path: Option<String>,
+
+    /// The byte offset of each line of the loaded config file, to tell the line of a span.
+    #[serde(skip)]
+    pub line_starts: Vec<usize>,
+}
+
+impl Default for RendererConfig {
+    /// Same as an empty config file, so the `serde(default)` values apply.
+    fn default() -> Self {
+        toml::from_str("").expect("an empty config is always valid")
+    }
 }
 
 impl RendererConfig {
-    fn default_header() -> String {
-        "use async_graphql::*; use crate::datasource::DataSource;".to_string()
+    /// The header written at the top of objects.rs.
+    pub fn header(&self) -> String {
+        match &self.header {
+            Some(header) => header.to_string(),
+            None => format!(
+                "use async_graphql::*; use {};",
+                self.module_path.data_source
+            ),
+        }
     }
 
-    fn default_data_source_fetch_method_from_ctx() -> String {
-        "ctx.data_unchecked::<DataSource>()".to_string()
+    /// Whether the nullable input values of the field of `type_name` are `MaybeUndefined<T>`.
+    pub fn maybe_undefined(&self, type_name: &str, field_setting: Option<&FieldSetting>) -> bool {
+        match field_setting.and_then(|field_setting| field_setting.maybe_undefined) {
+            Some(maybe_undefined) => maybe_undefined,
+            None => {
+                self.maybe_undefined
+                    || self
+                        .maybe_undefined_types
+                        .iter()
+                        .any(|each| each == type_name)
+            }
+        }
+    }
+
+    /// The rust type a primitive is rendered as.
+    pub fn primitive_type(&self, kind: &PrimitiveKind) -> String {
+        match self.primitive.get(kind) {
+            Some(rust_type) => rust_type.to_string(),
+            None => kind.rust_type(),
+        }
+    }
+
+    /// The config with the `@reverse` settings of the schema before its own entries, so the entries
+    /// of the config file win.
+    pub fn with_inline_settings(
+        &self,
+        inline_settings: &InlineSettings,
+    ) -> Cow<'_, RendererConfig> {
+        if inline_settings.is_empty() {
+            return Cow::Borrowed(self);
+        }
+        fn prepend<T: Clone>(entries: &mut Option<Vec<T>>, inline_entries: &[T]) {
+            let mut merged = inline_entries.to_vec();
+            merged.extend(entries.take().into_iter().flatten());
+            *entries = Some(merged);
+        }
+        let mut config = self.clone();
+        prepend(&mut config.resolver, &inline_settings.resolver);
+        prepend(&mut config.field, &inline_settings.field);
+        prepend(&mut config.r#enum, &inline_settings.r#enum);
+        Cow::Owned(config)
+    }
+
+    /// `[[type_mapping]]` and the mappings of `scalar_presets`. A later mapping of the same type wins.
+    pub fn type_mappings(&self) -> Vec<TypeMapping> {
+        let presets = self.scalar_presets.iter().flat_map(|preset| {
+            SCALAR_PRESETS
+                .iter()
+                .filter(move |(name, _, _)| name == preset)
+                .map(move |(_, graphql_type, rust_type)| TypeMapping {
+                    graphql_type: graphql_type.to_string(),
+                    rust_type: rust_type.to_string().into(),
+                    using: None,
+                    preset: Some(preset.to_string()),
+                })
+        });
+        presets
+            .chain(self.type_mapping.iter().flatten().cloned())
+            .collect()
+    }
+
+    /// The keys `[using]` accepts.
+    pub fn using_keys() -> Vec<&'static str> {
+        let mut keys = vec![USING_ALL, USING_DATASOURCE, USING_DATASOURCE_ALIAS];
+        keys.extend(Phase::all().iter().map(|phase| phase.module_name()));
+        keys
+    }
+
+    /// The use declarations `[using]` adds to `module`, with the entry each one comes from.
+    pub fn using(&self, module: &str) -> Vec<(String, String)> {
+        let using = match self.using.as_ref() {
+            None => return vec![],
+            Some(using) => using,
+        };
+        let aliases: &[&str] = if module == USING_DATASOURCE {
+            &[USING_DATASOURCE_ALIAS]
+        } else {
+            &[]
+        };
+        [USING_ALL, module]
+            .iter()
+            .chain(aliases)
+            .filter_map(|key| using.get(*key).map(|declarations| (key, declarations)))
+            .flat_map(|(key, declarations)| {
+                declarations
+                    .split(';')
+                    .map(str::trim)
+                    .filter(|declaration| !declaration.is_empty())
+                    .map(move |declaration| (format!("[using] {}", key), declaration.to_string()))
+            })
+            .collect()
+    }
+
+    pub fn phase_enabled(&self, phase: &Phase) -> bool {
+        self.phases.is_empty() || self.phases.contains(phase)
+    }
+
+    fn default_data_source_fetch_method_from_ctx() -> Located<String> {
+        "ctx.data_unchecked::<DataSource>()".to_string().into()
     }
 
     /// if a type contained this set, the field that has the type supposed to be a member instead of resolver method.
@@ -239,6 +1028,26 @@ impl RendererConfig {
         }
     }
 
+    pub fn scalar_settings(&self) -> HashMap<DefinedTypeName, ScalarSetting> {
+        match self.scalar.as_ref() {
+            None => HashMap::new(),
+            Some(scalar_settings) => scalar_settings
+                .iter()
+                .map(|each| (each.target_scalar.to_string(), each.clone()))
+                .collect(),
+        }
+    }
+
+    pub fn type_settings(&self) -> HashMap<DefinedTypeName, TypeSetting> {
+        match self.r#type.as_ref() {
+            None => HashMap::new(),
+            Some(type_settings) => type_settings
+                .iter()
+                .map(|each| (each.target_type.to_string(), each.clone()))
+                .collect(),
+        }
+    }
+
     pub fn hidden_fields(&self) -> HashMap<DefinedTypeName, HiddenFields> {
         match self.hidden_field.as_ref() {
             None => return HashMap::new(),
@@ -251,14 +1060,15 @@ impl RendererConfig {
                         let hidden_field = result
                             .entry(each_hidden_field.target_type.to_string())
                             .or_insert(HiddenFields {
+                                entry_name: each_hidden_field.entry_name(),
                                 using: vec![],
                                 field_defs: vec![],
                             });
                         hidden_field
                             .field_defs
-                            .push(each_hidden_field.field_def.clone());
+                            .push(each_hidden_field.field_def.to_string());
                         if let Some(using) = each_hidden_field.using.as_ref() {
-                            hidden_field.using.push(using.clone());
+                            hidden_field.using.push(using.to_string());
                         }
                     }
                     result
@@ -279,12 +1089,15 @@ impl RendererConfig {
                         let custom_resolvers = result
                             .entry(custom_resolver.target_type.to_string())
                             .or_insert(CustomResolvers {
+                                entry_name: custom_resolver.entry_name(),
                                 using: vec![],
                                 bodies: vec![],
                             });
-                        custom_resolvers.bodies.push(custom_resolver.body.clone());
+                        custom_resolvers
+                            .bodies
+                            .push(custom_resolver.body.to_string());
                         if let Some(using) = custom_resolver.using.as_ref() {
-                            custom_resolvers.using.push(using.clone());
+                            custom_resolvers.using.push(using.to_string());
                         }
                     }
                     result
@@ -294,8 +1107,37 @@ impl RendererConfig {
     }
 
     pub fn load(file_path: &str) -> Result<RendererConfig> {
-        let toml_str: String = fs::read_to_string(file_path)?;
-        let config: RendererConfig = toml::from_str(&toml_str).map_err(|e| anyhow!("{}", e))?;
+        let toml_str: String =
+            fs::read_to_string(file_path).map_err(|e| Error::file(file_path, e))?;
+        let load_error = |message: String| Error::ConfigLoad {
+            path: file_path.to_string(),
+            message,
+        };
+        let mut deserializer = toml::Deserializer::new(&toml_str);
+        let mut unknown_keys = Vec::<String>::new();
+        let mut config: RendererConfig = serde_ignored::deserialize(&mut deserializer, |path| {
+            unknown_keys.push(path.to_string())
+        })
+        .map_err(|e| load_error(e.to_string()))?;
+        config.unknown_keys = unknown_keys;
+        config.path = Some(file_path.to_string());
+        config.line_starts = std::iter::once(0)
+            .chain(toml_str.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
         Ok(config)
     }
+
+    /// `path:line` of a value of the loaded config file, or only the path when its span is unknown.
+    pub fn location(&self, span: Option<(usize, usize)>) -> Option<String> {
+        let path = self.path.as_ref()?;
+        match span {
+            Some((start, _)) if !self.line_starts.is_empty() => {
+                let line = self
+                    .line_starts
+                    .partition_point(|&line_start| line_start <= start);
+                Some(format!("{}:{}", path, line))
+            }
+            _ => Some(path.clone()),
+        }
+    }
 }
@@ -0,0 +1,145 @@
+use crate::config::Phase;
+use crate::validation::ConfigDiagnostic;
+use std::io;
+use strum::AsRefStr;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// The step of the generation in which an error occurred.
+#[derive(AsRefStr, Debug, Clone, Copy, PartialEq, Eq)]
+#[strum(serialize_all = "snake_case")]
+pub enum Stage {
+    Config,
+    Parse,
+    Objects,
+    InputObjects,
+    Unions,
+    Scalars,
+    Interfaces,
+    Enums,
+    Directives,
+    DataSource,
+    SchemaMod,
+}
+
+impl std::fmt::Display for Stage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
+impl From<&Phase> for Stage {
+    fn from(phase: &Phase) -> Self {
+        match phase {
+            Phase::Objects => Stage::Objects,
+            Phase::InputObjects => Stage::InputObjects,
+            Phase::Unions => Stage::Unions,
+            Phase::Scalars => Stage::Scalars,
+            Phase::Interfaces => Stage::Interfaces,
+            Phase::Enums => Stage::Enums,
+            Phase::Directives => Stage::Directives,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("{0}")]
+    Io(#[from] io::Error),
+
+    #[error("failed to access {path}: {source}")]
+    File {
+        path: String,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("failed to load config {path}: {message}")]
+    ConfigLoad { path: String, message: String },
+
+    /// A single entry of the config is invalid. `entry` names the entry as it is written in the config file.
+    #[error("invalid config entry {entry}: {message}")]
+    ConfigEntry { entry: String, message: String },
+
+    /// The config does not match the schema. Only returned in strict mode, otherwise these are warnings.
+    #[error("invalid config:{}", .0.iter().map(|d| format!("\n  {}", d)).collect::<String>())]
+    ConfigValidation(Vec<ConfigDiagnostic>),
+
+    /// A rust snippet written in the config does not parse as what the entry expects.
+    /// `location` is `path:line` of the snippet when the config is loaded from a file.
+    #[error("invalid {expected} in config entry {entry}{}: {message}", location.as_ref().map(|l| format!(" of {}", l)).unwrap_or_default())]
+    Snippet {
+        entry: String,
+        expected: String,
+        location: Option<String>,
+        message: String,
+    },
+
+    #[error("failed to parse schema: {0}")]
+    SchemaParse(String),
+
+    #[error("type {0} is not defined in the schema")]
+    UndefinedType(String),
+
+    #[error("field {0} is not defined in the schema, expected Type.field")]
+    UndefinedField(String),
+
+    #[error("the query type {0} is removed by include_tags or exclude_tags")]
+    QueryRemovedByTags(String),
+
+    #[error("{0} can not be the parent of fields")]
+    InvalidParent(String),
+
+    #[error("environment variable {0} is not set")]
+    EnvNotSet(String),
+
+    #[error("output path {0} is not a directory")]
+    NotADirectory(String),
+
+    #[error("rustfmt failed on {path}: {message}")]
+    Format { path: String, message: String },
+
+    /// Wraps an error with the phase and the schema element (`Type` or `Type.field`) being rendered.
+    #[error("[{stage}] {element}: {source}")]
+    Render {
+        stage: Stage,
+        element: String,
+        #[source]
+        source: Box<Error>,
+    },
+}
+
+impl Error {
+    pub fn file<P: AsRef<str>>(path: P, source: io::Error) -> Self {
+        Error::File {
+            path: path.as_ref().to_string(),
+            source,
+        }
+    }
+
+    pub fn config_entry<E: AsRef<str>, M: ToString>(entry: E, message: M) -> Self {
+        Error::ConfigEntry {
+            entry: entry.as_ref().to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+pub trait ResultExt<T> {
+    /// Attach the stage and the schema element to the error.
+    /// Errors that already name an element are passed through untouched so the innermost element wins.
+    fn in_element<E: AsRef<str>>(self, stage: Stage, element: E) -> Result<T>;
+}
+
+impl<T> ResultExt<T> for Result<T> {
+    fn in_element<E: AsRef<str>>(self, stage: Stage, element: E) -> Result<T> {
+        self.map_err(|e| match e {
+            e @ Error::Render { .. } => e,
+            e => Error::Render {
+                stage,
+                element: element.as_ref().to_string(),
+                source: Box::new(e),
+            },
+        })
+    }
+}
@@ -1,7 +1,12 @@
+pub mod build;
 mod config;
+mod error;
 mod parse;
 mod render;
+mod validation;
 
 pub use config::*;
+pub use error::*;
 pub use parse::*;
 pub use render::*;
+pub use validation::*;
@@ -1,6 +1,8 @@
+pub mod tags;
+
 pub use super::structured::*;
 use crate::config::{Ignore, RendererConfig};
-use anyhow::Result;
+use crate::error::Result;
 use std::collections::{HashMap, HashSet};
 
 pub fn remove_ignored_from_structure(
@@ -194,6 +196,34 @@ fn inner_remove_ignored_from_structure(
         }
     }
 
+    // === accumulate remove directive arguments =====================================================================
+    let mut remove_directive_argument_map: HashMap<String, HashSet<String>> = HashMap::new();
+    for directive in structured_schema.definitions.directives.values() {
+        for argument in directive.arguments.iter() {
+            if let Ok(typ) = argument
+                .typ
+                .element_value_type_def(&structured_schema.definitions)
+            {
+                let is_ignore_target = is_ignore_type!(
+                    typ,
+                    ignore_object_set,
+                    ignore_enums_set,
+                    ignore_input_object_set,
+                    ignore_scalar_set,
+                    ignore_union_set,
+                    ignore_interface_set
+                );
+
+                if is_ignore_target {
+                    remove_directive_argument_map
+                        .entry(directive.name.clone())
+                        .or_insert(HashSet::new())
+                        .insert(argument.name.clone());
+                }
+            }
+        }
+    }
+
     // remove object and field
 
     for object in structured_schema.definitions.objects.values_mut() {
@@ -230,6 +260,15 @@ fn inner_remove_ignored_from_structure(
             });
     }
 
+    // remove directive arguments
+    for directive in structured_schema.definitions.directives.values_mut() {
+        if let Some(remove_arguments) = remove_directive_argument_map.get(&directive.name) {
+            directive
+                .arguments
+                .retain(|argument| !remove_arguments.contains(&argument.name));
+        }
+    }
+
     // remove from definitions
     structured_schema
         .definitions
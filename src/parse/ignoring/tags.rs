@@ -0,0 +1,343 @@
+//! Schema variants by `@tag(name: "..")`. `exclude_tags` removes every type, field, argument, input
+//! field and enum value tagged with one of its names. With `include_tags` only the fields, input fields
+//! and enum values tagged with one of its names, or whose type is, are kept.
+//!
+//! The types left without fields, values or union members are removed as if they were in `[ignore]`,
+//! which removes the fields of their type in turn, until nothing changes.
+
+use super::*;
+use crate::config::{Ignore, RendererConfig, TAG_DIRECTIVE};
+use crate::error::{Error, Result};
+use std::collections::HashSet;
+
+pub fn remove_tagged_from_structure(
+    structured_schema: &mut StructuredSchema,
+    config: &RendererConfig,
+) -> Result<()> {
+    if config.include_tags.is_empty() && config.exclude_tags.is_empty() {
+        return Ok(());
+    }
+    let filter = TagFilter {
+        include: &config.include_tags,
+        exclude: &config.exclude_tags,
+    };
+
+    let definitions = &mut structured_schema.definitions;
+    for object in definitions.objects.values_mut() {
+        let object_tags = tags(&object.directives);
+        object
+            .fields
+            .retain(|field| filter.keeps(&field.directives, &object_tags));
+        for field in object.fields.iter_mut() {
+            field
+                .arguments
+                .retain(|argument| !filter.excludes(&argument.directives));
+        }
+    }
+    for interface in definitions.interfaces.values_mut() {
+        let interface_tags = tags(&interface.directives);
+        interface
+            .fields
+            .retain(|field| filter.keeps(&field.directives, &interface_tags));
+        for field in interface.fields.iter_mut() {
+            field
+                .arguments
+                .retain(|argument| !filter.excludes(&argument.directives));
+        }
+    }
+    for input_object in definitions.input_objects.values_mut() {
+        let input_object_tags = tags(&input_object.directives);
+        input_object
+            .fields
+            .retain(|field| filter.keeps(&field.directives, &input_object_tags));
+    }
+    for enm in definitions.enums.values_mut() {
+        let enum_tags = tags(&enm.directives);
+        enm.values
+            .retain(|value| filter.keeps(&value.directives, &enum_tags));
+    }
+
+    let mut removed = excluded_types(&structured_schema.definitions, &filter);
+    loop {
+        removed.extend(emptied_types(&structured_schema.definitions));
+        if removed.is_empty() {
+            break;
+        }
+        remove_types(structured_schema, &removed)?;
+        removed.clear();
+    }
+    Ok(())
+}
+
+struct TagFilter<'a> {
+    include: &'a [String],
+    exclude: &'a [String],
+}
+
+impl<'a> TagFilter<'a> {
+    fn excludes(&self, directives: &[Directive]) -> bool {
+        tags(directives)
+            .iter()
+            .any(|tag| self.exclude.contains(tag))
+    }
+
+    /// `parent_tags` are the tags of the type the element belongs to.
+    fn keeps(&self, directives: &[Directive], parent_tags: &[String]) -> bool {
+        let element_tags = tags(directives);
+        if element_tags.iter().any(|tag| self.exclude.contains(tag)) {
+            return false;
+        }
+        self.include.is_empty()
+            || element_tags
+                .iter()
+                .chain(parent_tags.iter())
+                .any(|tag| self.include.contains(tag))
+    }
+}
+
+/// The names of the `@tag` directives.
+fn tags(directives: &[Directive]) -> Vec<String> {
+    directives
+        .iter()
+        .filter(|directive| directive.name == TAG_DIRECTIVE)
+        .filter_map(|directive| match directive.argument("name") {
+            Some(async_graphql::Value::String(name)) => Some(name.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Every tag used in the schema.
+pub fn schema_tags(definitions: &Definitions) -> HashSet<String> {
+    let mut all_tags = HashSet::new();
+    let mut add = |directives: &[Directive]| all_tags.extend(tags(directives));
+    for object in definitions.objects.values() {
+        add(&object.directives);
+        for field in object.fields.iter() {
+            add(&field.directives);
+            field
+                .arguments
+                .iter()
+                .for_each(|each| add(&each.directives));
+        }
+    }
+    for interface in definitions.interfaces.values() {
+        add(&interface.directives);
+        for field in interface.fields.iter() {
+            add(&field.directives);
+            field
+                .arguments
+                .iter()
+                .for_each(|each| add(&each.directives));
+        }
+    }
+    for input_object in definitions.input_objects.values() {
+        add(&input_object.directives);
+        input_object
+            .fields
+            .iter()
+            .for_each(|each| add(&each.directives));
+    }
+    for enm in definitions.enums.values() {
+        add(&enm.directives);
+        enm.values.iter().for_each(|each| add(&each.directives));
+    }
+    definitions
+        .unions
+        .values()
+        .for_each(|each| add(&each.directives));
+    definitions
+        .scalars
+        .values()
+        .for_each(|each| add(&each.directives));
+    all_tags
+}
+
+fn excluded_types(definitions: &Definitions, filter: &TagFilter) -> HashSet<String> {
+    let mut names = HashSet::new();
+    let mut add = |name: &String, directives: &[Directive]| {
+        if filter.excludes(directives) {
+            names.insert(name.to_string());
+        }
+    };
+    definitions
+        .objects
+        .values()
+        .for_each(|each| add(&each.name, &each.directives));
+    definitions
+        .interfaces
+        .values()
+        .for_each(|each| add(&each.name, &each.directives));
+    definitions
+        .input_objects
+        .values()
+        .for_each(|each| add(&each.name, &each.directives));
+    definitions
+        .enums
+        .values()
+        .for_each(|each| add(&each.name, &each.directives));
+    definitions
+        .unions
+        .values()
+        .for_each(|each| add(&each.name, &each.directives));
+    definitions
+        .scalars
+        .values()
+        .for_each(|each| add(&each.name, &each.directives));
+    names
+}
+
+fn emptied_types(definitions: &Definitions) -> HashSet<String> {
+    let objects = definitions
+        .objects
+        .values()
+        .filter(|each| each.fields.is_empty())
+        .map(|each| &each.name);
+    let interfaces = definitions
+        .interfaces
+        .values()
+        .filter(|each| each.fields.is_empty())
+        .map(|each| &each.name);
+    let input_objects = definitions
+        .input_objects
+        .values()
+        .filter(|each| each.fields.is_empty())
+        .map(|each| &each.name);
+    let enums = definitions
+        .enums
+        .values()
+        .filter(|each| each.values.is_empty())
+        .map(|each| &each.name);
+    let unions = definitions
+        .unions
+        .values()
+        .filter(|each| each.type_names.is_empty())
+        .map(|each| &each.name);
+    objects
+        .chain(interfaces)
+        .chain(input_objects)
+        .chain(enums)
+        .chain(unions)
+        .map(|name| name.to_string())
+        .collect()
+}
+
+/// Remove the types like `[ignore]` does, with the union members and interfaces naming them.
+fn remove_types(structured_schema: &mut StructuredSchema, removed: &HashSet<String>) -> Result<()> {
+    if let Some(query_name) = &structured_schema.query_name {
+        if removed.contains(query_name) {
+            return Err(Error::QueryRemovedByTags(query_name.to_string()));
+        }
+    }
+    let definitions = &structured_schema.definitions;
+    let of = |names: Vec<&String>| -> Option<HashSet<String>> {
+        Some(
+            names
+                .into_iter()
+                .filter(|name| removed.contains(*name))
+                .cloned()
+                .collect(),
+        )
+    };
+    let ignore = Ignore {
+        r#enum: of(definitions.enums.keys().collect()),
+        object: of(definitions.objects.keys().collect()),
+        input_object: of(definitions.input_objects.keys().collect()),
+        union: of(definitions.unions.keys().collect()),
+        interface: of(definitions.interfaces.keys().collect()),
+        scalar: of(definitions.scalars.keys().collect()),
+    };
+    inner_remove_ignored_from_structure(structured_schema, &ignore)?;
+
+    let definitions = &mut structured_schema.definitions;
+    for union in definitions.unions.values_mut() {
+        union.type_names.retain(|name| !removed.contains(name));
+    }
+    for object in definitions.objects.values_mut() {
+        object
+            .impl_interface_name
+            .retain(|name| !removed.contains(name));
+    }
+    for name in [
+        &mut structured_schema.mutation_name,
+        &mut structured_schema.subscription_name,
+    ]
+    .iter_mut()
+    {
+        if name.as_ref().map_or(false, |name| removed.contains(name)) {
+            **name = None;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::parse::parse_schema;
+
+    #[test]
+    pub fn remove_tagged_cascades() {
+        let schema = r#"
+        schema {
+          query: Query
+          mutation: Mutation
+        }
+
+        type Query {
+          me: User! @tag(name: "public")
+          audit: [AuditLog!]! @tag(name: "public")
+          debug: String!
+        }
+
+        type Mutation {
+          purge: Boolean!
+        }
+
+        type User @tag(name: "public") {
+          name: String!
+          role: Role!
+          token(scope: String @tag(name: "internal")): String! @tag(name: "internal")
+          logs: [AuditLog!]!
+        }
+
+        type AuditLog @tag(name: "internal") {
+          message: String!
+        }
+
+        union Entry = User | AuditLog
+
+        enum Role {
+          ADMIN @tag(name: "public")
+          STAFF @tag(name: "internal")
+        }
+        "#;
+        let mut config = RendererConfig::default();
+        config.include_tags = vec!["public".to_string()];
+        config.exclude_tags = vec!["internal".to_string()];
+        let mut structured_schema = parse_schema(schema, &config).unwrap();
+        remove_tagged_from_structure(&mut structured_schema, &config).unwrap();
+
+        let definitions = &structured_schema.definitions;
+        let field_names = |name: &str| -> Vec<String> {
+            definitions.objects[name]
+                .fields
+                .iter()
+                .map(|field| field.name.to_string())
+                .collect()
+        };
+        assert_eq!(field_names("Query"), vec!["me"]);
+        assert_eq!(field_names("User"), vec!["name", "role"]);
+        assert!(!definitions.objects.contains_key("AuditLog"));
+        assert!(!definitions.objects.contains_key("Mutation"));
+        assert_eq!(structured_schema.mutation_name, None);
+        assert_eq!(definitions.unions["Entry"].type_names, vec!["User"]);
+        assert_eq!(definitions.enums["Role"].values.len(), 1);
+
+        config.include_tags = vec!["internal".to_string()];
+        config.exclude_tags = vec![];
+        let mut structured_schema = parse_schema(schema, &config).unwrap();
+        assert!(remove_tagged_from_structure(&mut structured_schema, &config).is_err());
+    }
+}
@@ -0,0 +1,240 @@
+//! Settings written in the schema with the `@reverse` directive instead of the config file.
+//!
+//! - `@reverse(resolver: "field", attribute: "..", rustType: "..")` on a field of an object, like
+//!   `[[resolver]] resolver_type`/`attribute` and `[[field]] primitive_type`,
+//! - `@reverse(rustType: "..")` on a field of an interface or an input object,
+//! - `@reverse(renameItems: "..")` on an enum and `@reverse(rename: "..")` on its values, like
+//!   `[[enum]] rename_items` and `value.rename`.
+//!
+//! An entry of the config file for the same field or enum replaces the `@reverse` one.
+
+use super::structured::*;
+use crate::config::*;
+use async_graphql_parser::{types as async_gql_types, Positioned as AsyncGqlPositioned};
+use heck::CamelCase;
+use std::collections::HashMap;
+
+const OBJECT_FIELD_ARGUMENTS: &[&str] = &["resolver", "attribute", "rustType"];
+const FIELD_ARGUMENTS: &[&str] = &["rustType"];
+const ENUM_ARGUMENTS: &[&str] = &["renameItems"];
+const ENUM_VALUE_ARGUMENTS: &[&str] = &["rename"];
+
+pub fn inline_settings(service_document: &async_gql_types::ServiceDocument) -> InlineSettings {
+    let mut settings = InlineSettings::default();
+    for each_node in service_document.definitions.iter() {
+        let type_def = match each_node {
+            async_gql_types::TypeSystemDefinition::Type(type_def) => &type_def.node,
+            _ => continue,
+        };
+        let type_name = type_def.name.node.to_string();
+        match &type_def.kind {
+            async_gql_types::TypeKind::Object(object) => {
+                warn_on_type(&type_def.directives, &type_name);
+                for field in object.fields.iter() {
+                    let field_name = field.node.name.node.to_string();
+                    let element = format!("{}.{}", type_name, field_name);
+                    let arguments =
+                        reverse_arguments(&field.node.directives, OBJECT_FIELD_ARGUMENTS, &element);
+                    if arguments.contains_key("resolver") || arguments.contains_key("attribute") {
+                        settings.resolver.push(ResolverSetting {
+                            target_type: type_name.clone(),
+                            target_field: field_name.clone(),
+                            resolver_type: arguments.get("resolver").cloned(),
+                            attribute: arguments.get("attribute").cloned().map(Located::new),
+                            argument: None,
+                            inline: true,
+                        });
+                    }
+                    add_field_setting(&mut settings, &type_name, &field_name, &arguments);
+                }
+            }
+            async_gql_types::TypeKind::Interface(interface) => {
+                warn_on_type(&type_def.directives, &type_name);
+                for field in interface.fields.iter() {
+                    let field_name = field.node.name.node.to_string();
+                    let element = format!("{}.{}", type_name, field_name);
+                    let arguments =
+                        reverse_arguments(&field.node.directives, FIELD_ARGUMENTS, &element);
+                    add_field_setting(&mut settings, &type_name, &field_name, &arguments);
+                }
+            }
+            async_gql_types::TypeKind::InputObject(input_object) => {
+                warn_on_type(&type_def.directives, &type_name);
+                for field in input_object.fields.iter() {
+                    let field_name = field.node.name.node.to_string();
+                    let element = format!("{}.{}", type_name, field_name);
+                    let arguments =
+                        reverse_arguments(&field.node.directives, FIELD_ARGUMENTS, &element);
+                    add_field_setting(&mut settings, &type_name, &field_name, &arguments);
+                }
+            }
+            async_gql_types::TypeKind::Enum(enum_type) => {
+                let arguments = reverse_arguments(&type_def.directives, ENUM_ARGUMENTS, &type_name);
+                let values: Vec<EnumValueSetting> = enum_type
+                    .values
+                    .iter()
+                    .filter_map(|value| {
+                        let value_name = value.node.value.node.to_string();
+                        let element = format!("{}.{}", type_name, value_name);
+                        let arguments = reverse_arguments(
+                            &value.node.directives,
+                            ENUM_VALUE_ARGUMENTS,
+                            &element,
+                        );
+                        let rename = arguments.get("rename")?;
+                        Some(EnumValueSetting {
+                            value: value_name.to_camel_case(),
+                            rename: Some(rename.to_string()),
+                            visible: None,
+                        })
+                    })
+                    .collect();
+                if arguments.contains_key("renameItems") || !values.is_empty() {
+                    settings.r#enum.push(EnumSetting {
+                        target_enum: type_name.to_camel_case(),
+                        rename_items: arguments.get("renameItems").cloned(),
+                        value: if values.is_empty() {
+                            None
+                        } else {
+                            Some(values)
+                        },
+                        inline: true,
+                    });
+                }
+            }
+            async_gql_types::TypeKind::Union(_) | async_gql_types::TypeKind::Scalar => {
+                warn_on_type(&type_def.directives, &type_name);
+            }
+        }
+    }
+    settings
+}
+
+fn add_field_setting(
+    settings: &mut InlineSettings,
+    type_name: &str,
+    field_name: &str,
+    arguments: &HashMap<String, String>,
+) {
+    if let Some(rust_type) = arguments.get("rustType") {
+        settings.field.push(FieldSetting {
+            target_type: type_name.to_string(),
+            target_field: field_name.to_string(),
+            replace_field_type: None,
+            primitive_type: Some(rust_type.to_string().into()),
+            maybe_undefined: None,
+            id_of: None,
+            argument_id_of: None,
+            visible: None,
+            secret: None,
+            secret_arguments: None,
+            inline: true,
+        });
+    }
+}
+
+/// `@reverse` has no settings on the types other than enums.
+fn warn_on_type(
+    directives: &[AsyncGqlPositioned<async_gql_types::ConstDirective>],
+    type_name: &str,
+) {
+    reverse_arguments(directives, &[], type_name);
+}
+
+/// The string arguments of `@reverse`, warning about the ones not supported on the element.
+fn reverse_arguments(
+    directives: &[AsyncGqlPositioned<async_gql_types::ConstDirective>],
+    supported: &[&str],
+    element: &str,
+) -> HashMap<String, String> {
+    let directives = convert_directives(directives);
+    let directive = match find_directive(&directives, REVERSE_DIRECTIVE) {
+        Some(directive) => directive,
+        None => return HashMap::new(),
+    };
+    let mut arguments = HashMap::<String, String>::new();
+    if directive.arguments.is_empty() {
+        log::warn!("@{} of {} has no arguments", REVERSE_DIRECTIVE, element);
+    }
+    for (name, value) in directive.arguments.iter() {
+        if !supported.contains(&name.as_str()) {
+            log::warn!(
+                "{} of @{} is not supported on {}",
+                name,
+                REVERSE_DIRECTIVE,
+                element
+            );
+            continue;
+        }
+        match value {
+            async_graphql::Value::String(value) => {
+                arguments.insert(name.to_string(), value.to_string());
+            }
+            value => log::warn!(
+                "invalid {} {} of @{} of {}, expected a string",
+                name,
+                value,
+                REVERSE_DIRECTIVE,
+                element
+            ),
+        }
+    }
+    arguments
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn read_reverse_directives() {
+        let schema = r##"
+        type Query {
+          me: User @reverse(resolver: "field", attribute: "#[allow(unused)]")
+          count: Int @reverse(rustType: "u32")
+        }
+        type User {
+          id: ID!
+        }
+        enum Status @reverse(renameItems: "lowercase") {
+          ACTIVE @reverse(rename: "enabled")
+          DELETED
+        }
+        "##;
+        let document = async_graphql_parser::parse_schema(schema).unwrap();
+        let settings = inline_settings(&document);
+
+        assert_eq!(settings.resolver.len(), 1);
+        assert_eq!(settings.resolver[0].entry_name(), "@reverse of Query.me");
+        assert_eq!(settings.resolver[0].resolver_type.as_deref(), Some("field"));
+        assert_eq!(
+            settings.resolver[0]
+                .attribute
+                .as_deref()
+                .map(String::as_str),
+            Some("#[allow(unused)]")
+        );
+        assert_eq!(settings.field.len(), 1);
+        assert_eq!(settings.field[0].target_field, "count");
+        assert_eq!(
+            settings.field[0]
+                .primitive_type
+                .as_deref()
+                .map(String::as_str),
+            Some("u32")
+        );
+        assert_eq!(
+            settings.r#enum,
+            vec![EnumSetting {
+                target_enum: "Status".to_string(),
+                rename_items: Some("lowercase".to_string()),
+                value: Some(vec![EnumValueSetting {
+                    value: "Active".to_string(),
+                    rename: Some("enabled".to_string()),
+                    visible: None,
+                }]),
+                inline: true,
+            }]
+        );
+    }
+}
@@ -1,37 +1,63 @@
 pub mod ignoring;
+pub mod inline_settings;
 pub mod structured;
+pub mod typed_ids;
 use crate::config::RendererConfig;
+use crate::error::{Error, Result};
+use crate::validation::check_config;
 pub use structured::*;
 
-use anyhow::{anyhow, Result};
-
 use std::fs;
+use std::path::Path;
 
 pub fn parse_schema_file(path: &str, config: &RendererConfig) -> Result<StructuredSchema> {
-    match fs::read_to_string(path) {
-        Ok(mut schema_body) => {
-            if let Some(additionals) = &config.additional {
-                let merged_additional = additionals
-                    .iter()
-                    .map(|each| each.body.to_string())
-                    .collect::<Vec<String>>()
-                    .join(" ");
-
-                schema_body = format!("{} {}", schema_body, merged_additional);
-            }
-
-            let mut schema = parse_schema(&schema_body, config)?;
-
-            ignoring::remove_ignored_from_structure(&mut schema, &config)?;
-            Ok(schema)
-        }
-        Err(e) => Err(anyhow!("{}", e)),
+    parse_schema_files(&[path], config)
+}
+
+/// Parse the schema split into several files as one schema.
+pub fn parse_schema_files<P: AsRef<Path>>(
+    paths: &[P],
+    config: &RendererConfig,
+) -> Result<StructuredSchema> {
+    parse_and_filter_schema_files(paths, config).map(|(_, schema)| schema)
+}
+
+/// The schema as parsed and the one after `[ignore]` and the tags are applied.
+pub fn parse_and_filter_schema_files<P: AsRef<Path>>(
+    paths: &[P],
+    config: &RendererConfig,
+) -> Result<(StructuredSchema, StructuredSchema)> {
+    let mut schema_body = String::new();
+    for path in paths {
+        let body = fs::read_to_string(path)
+            .map_err(|e| Error::file(path.as_ref().display().to_string(), e))?;
+        schema_body = format!("{} {}", schema_body, body);
     }
+
+    if let Some(additionals) = &config.additional {
+        let merged_additional = additionals
+            .iter()
+            .map(|each| each.body.to_string())
+            .collect::<Vec<String>>()
+            .join(" ");
+
+        schema_body = format!("{} {}", schema_body, merged_additional);
+    }
+
+    let parsed = parse_schema(&schema_body, config)?;
+    let mut schema = parsed.clone();
+
+    let config = &*config.with_inline_settings(&parsed.inline_settings);
+    ignoring::remove_ignored_from_structure(&mut schema, config)?;
+    // a config is shared by the variants of the schema, so what only the tags remove is not reported.
+    check_config(config, &parsed, &schema)?;
+    ignoring::tags::remove_tagged_from_structure(&mut schema, config)?;
+    Ok((parsed, schema))
 }
 pub fn parse_schema(schema_body: &str, config: &RendererConfig) -> Result<StructuredSchema> {
     match async_graphql_parser::parse_schema(schema_body) {
         Ok(schema) => convert_to_structured_schema(schema, config),
-        Err(e) => Err(anyhow!("{}", e)),
+        Err(e) => Err(Error::SchemaParse(e.to_string())),
     }
 }
 
@@ -39,6 +65,7 @@ pub fn parse_schema(schema_body: &str, config: &RendererConfig) -> Result<Struct
 mod test {
 
     use super::*;
+    use crate::config::InlineSettings;
     #[test]
     pub fn parse_schema_input_1() {
         let schema = r#"
@@ -62,6 +89,12 @@ mod test {
                             is_nullable: true,
                         }),
                         line_pos: 3,
+                        primitive_type: None,
+                        maybe_undefined: false,
+                        directives: vec![],
+                        visible: None,
+                        secret: false,
+                        features: vec![],
                     },
                     InputField {
                         name: "rec".to_string(),
@@ -74,10 +107,19 @@ mod test {
                             is_nullable: true,
                         }),
                         line_pos: 4,
+                        primitive_type: None,
+                        maybe_undefined: false,
+                        directives: vec![],
+                        visible: None,
+                        secret: false,
+                        features: vec![],
                     },
                 ],
                 description: None,
                 line_pos: 2,
+                directives: vec![],
+                visible: None,
+                features: vec![],
             },
         );
 
@@ -86,8 +128,64 @@ mod test {
             mutation_name: None,
             subscription_name: None,
             definitions,
+            inline_settings: InlineSettings::default(),
         };
 
         assert_eq!(result, expected);
     }
+
+    #[test]
+    pub fn report_ignored_but_not_tagged_targets() {
+        let schema = r#"
+        type Query {
+          me: User!
+          audit: [Audit!]! @tag(name: "internal")
+        }
+
+        type User {
+          name: String!
+        }
+
+        type Audit @tag(name: "internal") {
+          id: ID!
+        }
+        "#;
+        let path = std::env::temp_dir().join("report_ignored_but_not_tagged_targets.graphql");
+        fs::write(&path, schema).unwrap();
+        let config_str = r#"
+            strict = true
+            exclude_tags = ["internal"]
+
+            [[resolver]]
+            target_type = "Audit"
+            target_field = "id"
+            resolver_type = "method"
+            "#;
+
+        let config: RendererConfig = toml::from_str(config_str).unwrap();
+        let (_, filtered) = parse_and_filter_schema_files(&[&path], &config).unwrap();
+        assert!(!filtered.definitions.objects.contains_key("Audit"));
+
+        let config: RendererConfig =
+            toml::from_str(&format!("{}\n[ignore]\nobject = [\"Audit\"]", config_str)).unwrap();
+        let error = parse_and_filter_schema_files(&[&path], &config).unwrap_err();
+        assert!(error.to_string().contains("object Audit is ignored"));
+    }
+
+    #[test]
+    pub fn return_typed_errors_on_invalid_input() {
+        let config = RendererConfig::default();
+        let result = parse_schema("type Query {", &config);
+        assert!(matches!(result, Err(Error::SchemaParse(_))));
+
+        let schema = parse_schema("type Query { me: User! }", &config).unwrap();
+        let field = &schema.definitions.objects["Query"].fields[0];
+        let result = field.typ.element_value_type_def(&schema.definitions);
+        assert!(matches!(result, Err(Error::UndefinedType(name)) if name == "User"));
+
+        let path = std::env::temp_dir().join("return_typed_errors_on_invalid_input.toml");
+        fs::write(&path, "phases = [").unwrap();
+        let result = RendererConfig::load(path.to_str().unwrap());
+        assert!(matches!(result, Err(Error::ConfigLoad { .. })));
+    }
 }
@@ -1,8 +1,12 @@
 pub mod schema;
 use crate::config::*;
+use crate::error::{Error, Result};
+use crate::parse::inline_settings::inline_settings;
+use crate::parse::typed_ids::add_typed_ids;
+use heck::CamelCase;
 pub use schema::*;
+use std::collections::HashMap;
 
-use anyhow::{anyhow, Result};
 use async_graphql_parser::{types as async_gql_types, Positioned as AsyncGqlPositioned};
 
 macro_rules! node_as_string {
@@ -15,6 +19,9 @@ pub fn convert_to_structured_schema(
     service_document: async_gql_types::ServiceDocument,
     config: &RendererConfig,
 ) -> Result<StructuredSchema> {
+    let inline_settings = inline_settings(&service_document);
+    let config = &*config.with_inline_settings(&inline_settings);
+
     let mut query_name: Option<String> = None;
     let mut mutation_name: Option<String> = None;
     let mut subscription_name: Option<String> = None;
@@ -37,50 +44,301 @@ pub fn convert_to_structured_schema(
             }
 
             async_gql_types::TypeSystemDefinition::Type(type_def) => {
-                definitions.add_definition(convert_type_def(type_def, config));
+                definitions.add_definition(convert_type_def(type_def, config)?);
             }
 
             async_gql_types::TypeSystemDefinition::Directive(directive_def) => {
-                log::warn!(
-                    "directive not supported yet :{}",
-                    directive_def.node.name.node
-                );
+                if let Some(directive) = convert_directive_def(directive_def, config) {
+                    definitions
+                        .directives
+                        .insert(directive.name_string(), directive);
+                }
             }
         }
     }
 
+    for type_mapping in config.type_mappings().iter() {
+        definitions.map_type(
+            &type_mapping.graphql_type,
+            MappedType {
+                name: type_mapping.graphql_type.to_string(),
+                rust_type: type_mapping.rust_type.to_string(),
+                using: type_mapping.using.as_deref().cloned(),
+                entry_name: type_mapping.entry_name(),
+                impl_interface_name: vec![],
+            },
+        );
+    }
+
+    warn_unsupported_directives(&definitions, config);
+    add_typed_ids(&mut definitions, config);
+    apply_maybe_undefined(&mut definitions, config);
+    apply_visibility(&mut definitions, config);
+    apply_features(&mut definitions, config);
+
     Ok(StructuredSchema {
         query_name,
         mutation_name,
         subscription_name,
         definitions,
+        inline_settings,
     })
 }
 
+/// Mark the input fields and arguments `maybe_undefined` applies to.
+fn apply_maybe_undefined(definitions: &mut Definitions, config: &RendererConfig) {
+    let field_settings = config.field_setting();
+    let field_setting = |type_name: &str, field_name: &str| {
+        field_settings
+            .get(type_name)
+            .and_then(|fields_setting| fields_setting.get(field_name))
+            .copied()
+    };
+    for object in definitions.objects.values_mut() {
+        for field in object.fields.iter_mut() {
+            let maybe_undefined =
+                config.maybe_undefined(&object.name, field_setting(&object.name, &field.name));
+            for argument in field.arguments.iter_mut() {
+                argument.maybe_undefined = maybe_undefined;
+            }
+        }
+    }
+    for input_object in definitions.input_objects.values_mut() {
+        for field in input_object.fields.iter_mut() {
+            field.maybe_undefined = config.maybe_undefined(
+                &input_object.name,
+                field_setting(&input_object.name, &field.name),
+            );
+        }
+    }
+}
+
+fn internal(directives: &[Directive]) -> Option<Visible> {
+    find_directive(directives, INTERNAL_DIRECTIVE).map(|_| Visible::Bool(false))
+}
+
+fn sensitive(directives: &[Directive]) -> bool {
+    find_directive(directives, SENSITIVE_DIRECTIVE).is_some()
+}
+
+macro_rules! apply_type_visibility {
+    ($definitions:expr, $type_settings:ident) => {
+        for each in $definitions.values_mut() {
+            each.visible = $type_settings
+                .get(&each.name)
+                .and_then(|type_setting| type_setting.visible.as_deref().cloned())
+                .or_else(|| internal(&each.directives));
+        }
+    };
+}
+
+/// Set `visible` and `secret` of the elements from the config, or else from `@internal` and `@sensitive`.
+fn apply_visibility(definitions: &mut Definitions, config: &RendererConfig) {
+    let type_settings = config.type_settings();
+    apply_type_visibility!(definitions.objects, type_settings);
+    apply_type_visibility!(definitions.input_objects, type_settings);
+    apply_type_visibility!(definitions.enums, type_settings);
+    apply_type_visibility!(definitions.interfaces, type_settings);
+    apply_type_visibility!(definitions.unions, type_settings);
+    apply_type_visibility!(definitions.scalars, type_settings);
+
+    let field_settings = config.field_setting();
+    let field_setting = |type_name: &str, field_name: &str| {
+        field_settings
+            .get(type_name)
+            .and_then(|fields_setting| fields_setting.get(field_name))
+            .copied()
+    };
+    for object in definitions.objects.values_mut() {
+        for field in object.fields.iter_mut() {
+            let setting = field_setting(&object.name, &field.name);
+            field.visible = setting
+                .and_then(|setting| setting.visible.as_deref().cloned())
+                .or_else(|| internal(&field.directives));
+            let secret_arguments = setting.and_then(|setting| setting.secret_arguments.as_ref());
+            for argument in field.arguments.iter_mut() {
+                argument.secret = secret_arguments.map_or(false, |secret_arguments| {
+                    secret_arguments.contains(&argument.name)
+                }) || sensitive(&argument.directives);
+            }
+        }
+    }
+    for input_object in definitions.input_objects.values_mut() {
+        for field in input_object.fields.iter_mut() {
+            let setting = field_setting(&input_object.name, &field.name);
+            field.visible = setting
+                .and_then(|setting| setting.visible.as_deref().cloned())
+                .or_else(|| internal(&field.directives));
+            field.secret = setting
+                .and_then(|setting| setting.secret)
+                .unwrap_or_else(|| sensitive(&field.directives));
+        }
+    }
+
+    let enum_settings = config.enum_settings();
+    for enm in definitions.enums.values_mut() {
+        let value_settings = enum_settings
+            .get(&enm.name.to_camel_case())
+            .and_then(|enum_setting| enum_setting.value.as_ref());
+        for value in enm.values.iter_mut() {
+            let value_name = value.value_name.to_camel_case();
+            value.visible = value_settings
+                .and_then(|value_settings| {
+                    value_settings.iter().find(|each| each.value == value_name)
+                })
+                .and_then(|value_setting| value_setting.visible.as_deref().cloned())
+                .or_else(|| internal(&value.directives));
+        }
+    }
+}
+
+/// The type of the field and of its arguments.
+fn field_types(field: &Field) -> Vec<&ValueTypeDef> {
+    std::iter::once(&field.typ)
+        .chain(field.arguments.iter().map(|argument| &argument.typ))
+        .collect()
+}
+
+macro_rules! apply_type_features {
+    ($definitions:expr, $feature_of:ident) => {
+        for each in $definitions.values_mut() {
+            each.features = $feature_of(&each.name);
+        }
+    };
+}
+
+/// Gate the types by `[[type]] feature`, a typed ID by the feature of its object, and the fields by
+/// the features of the types of them and of their arguments.
+fn apply_features(definitions: &mut Definitions, config: &RendererConfig) {
+    let type_settings = config.type_settings();
+    let feature_of = |type_name: &str| -> Vec<String> {
+        type_settings
+            .get(type_name)
+            .and_then(|type_setting| type_setting.feature.clone())
+            .into_iter()
+            .collect()
+    };
+    apply_type_features!(definitions.objects, feature_of);
+    apply_type_features!(definitions.input_objects, feature_of);
+    apply_type_features!(definitions.enums, feature_of);
+    apply_type_features!(definitions.interfaces, feature_of);
+    apply_type_features!(definitions.unions, feature_of);
+    apply_type_features!(definitions.scalars, feature_of);
+    for scalar in definitions.scalars.values_mut() {
+        if let Some(owner) = &scalar.id_of {
+            scalar.features = feature_of(owner);
+        }
+    }
+
+    let mut gated_types = HashMap::<String, Vec<String>>::new();
+    let scalars = definitions
+        .scalars
+        .values()
+        .map(|each| (&each.name, &each.features));
+    let enums = definitions
+        .enums
+        .values()
+        .map(|each| (&each.name, &each.features));
+    let input_objects = definitions
+        .input_objects
+        .values()
+        .map(|each| (&each.name, &each.features));
+    let objects = definitions
+        .objects
+        .values()
+        .map(|each| (&each.name, &each.features));
+    let interfaces = definitions
+        .interfaces
+        .values()
+        .map(|each| (&each.name, &each.features));
+    let unions = definitions
+        .unions
+        .values()
+        .map(|each| (&each.name, &each.features));
+    for (name, features) in scalars
+        .chain(enums)
+        .chain(input_objects)
+        .chain(objects)
+        .chain(interfaces)
+        .chain(unions)
+    {
+        if !features.is_empty() {
+            gated_types.insert(name.to_string(), features.clone());
+        }
+    }
+    if gated_types.is_empty() {
+        return;
+    }
+    let features_of = |types: Vec<&ValueTypeDef>, parent_features: &[String]| -> Vec<String> {
+        let mut features: Vec<String> = types
+            .into_iter()
+            .filter_map(|typ| gated_types.get(typ.element_type_name()))
+            .flat_map(|features| features.iter())
+            .filter(|feature| !parent_features.contains(*feature))
+            .cloned()
+            .collect();
+        features.sort();
+        features.dedup();
+        features
+    };
+
+    for object in definitions.objects.values_mut() {
+        for field in object.fields.iter_mut() {
+            field.features = features_of(field_types(field), &object.features);
+        }
+    }
+    for interface in definitions.interfaces.values_mut() {
+        for field in interface.fields.iter_mut() {
+            field.features = features_of(field_types(field), &interface.features);
+        }
+    }
+    for input_object in definitions.input_objects.values_mut() {
+        for field in input_object.fields.iter_mut() {
+            field.features = features_of(vec![&field.typ], &input_object.features);
+        }
+    }
+    for directive in definitions.directives.values_mut() {
+        let argument_types = directive.arguments.iter().map(|each| &each.typ).collect();
+        directive.features = features_of(argument_types, &[]);
+    }
+}
+
 fn convert_type_def(
     type_def: AsyncGqlPositioned<async_gql_types::TypeDefinition>,
     config: &RendererConfig,
-) -> Definition {
+) -> Result<Definition> {
     let line_pos = type_def.pos.line;
     let type_def = type_def.node;
 
     let type_def_name = node_as_string!(type_def.name);
     let description = type_def.description.map(|desc| node_as_string!(desc));
+    let directives = convert_directives(&type_def.directives);
     let resolver_settings = config.resolver_setting();
     let field_settings = config.field_setting();
 
-    match type_def.kind {
+    let definition = match type_def.kind {
         async_gql_types::TypeKind::Scalar => Definition::Scalar(Scalar {
             name: type_def_name,
             line_pos,
+            specified_by_url: find_directive(&directives, "specifiedBy")
+                .and_then(|directive| directive.argument("url"))
+                .and_then(|url| match url {
+                    async_graphql::Value::String(url) => Some(url.clone()),
+                    _ => None,
+                }),
+            id_of: None,
+            directives,
+            visible: None,
+            features: vec![],
         }),
         async_gql_types::TypeKind::Object(object_type) => {
             let fields_resolver_setting = resolver_settings.get(&type_def_name);
             let fields_setting = field_settings.get(&type_def_name);
 
             let fields =
-                convert_fields(&object_type.fields, fields_setting, fields_resolver_setting);
+                convert_fields(&object_type.fields, fields_setting, fields_resolver_setting)?;
 
+            let cache_control = cache_control(&directives, &type_def_name);
             let object = Object {
                 name: type_def_name,
                 fields,
@@ -91,13 +349,26 @@ fn convert_type_def(
                     .into_iter()
                     .map(|implement| node_as_string!(implement))
                     .collect(),
+                cache_control,
+                directives,
+                visible: None,
+                features: vec![],
             };
 
             Definition::Object(object)
         }
         async_gql_types::TypeKind::Interface(interface) => {
             let fields_setting = field_settings.get(&type_def_name);
-            let fields = convert_fields(&interface.fields, fields_setting, None);
+            let fields = convert_fields(&interface.fields, fields_setting, None)?;
+            let unsupported_cache_control = std::iter::once(&directives)
+                .chain(fields.iter().map(|field| &field.directives))
+                .any(|directives| find_directive(directives, CACHE_CONTROL_DIRECTIVE).is_some());
+            if unsupported_cache_control {
+                log::warn!(
+                    "@cacheControl of interface {} is not supported, set it on the objects",
+                    type_def_name
+                );
+            }
 
             let intf = Interface {
                 name: type_def_name,
@@ -110,6 +381,9 @@ fn convert_type_def(
                 fields,
                 line_pos,
                 description,
+                directives,
+                visible: None,
+                features: vec![],
             };
 
             Definition::Interface(intf)
@@ -126,11 +400,20 @@ fn convert_type_def(
                 .map(|member| node_as_string!(member))
                 .collect();
 
+            if find_directive(&directives, CACHE_CONTROL_DIRECTIVE).is_some() {
+                log::warn!(
+                    "@cacheControl of union {} is not supported, set it on the objects",
+                    type_def_name
+                );
+            }
             let union = Union {
                 name: type_def_name,
                 type_names,
                 line_pos,
                 description,
+                directives,
+                visible: None,
+                features: vec![],
             };
 
             Definition::Union(union)
@@ -147,6 +430,9 @@ fn convert_type_def(
                 values: enum_values,
                 line_pos,
                 description,
+                directives,
+                visible: None,
+                features: vec![],
             };
 
             Definition::Enum(enum_def)
@@ -157,35 +443,268 @@ fn convert_type_def(
                 .fields
                 .iter()
                 .map(|input_field| convert_input_field_def(input_field, fields_setting))
-                .collect();
+                .collect::<Result<Vec<InputField>>>()?;
 
             let input_object = InputObject {
                 name: type_def_name,
                 fields: input_fields,
                 description,
                 line_pos,
+                directives,
+                visible: None,
+                features: vec![],
             };
 
             Definition::InputObject(input_object)
         }
+    };
+    Ok(definition)
+}
+
+/// The directives the generator reads. The others are kept on the nodes but warned about.
+const SUPPORTED_DIRECTIVES: &[&str] = &[
+    "specifiedBy",
+    INTERNAL_DIRECTIVE,
+    SENSITIVE_DIRECTIVE,
+    TAG_DIRECTIVE,
+    CACHE_CONTROL_DIRECTIVE,
+    REVERSE_DIRECTIVE,
+];
+
+/// Whether the directive is read by the generator, mapped by `[[directive_mapping]]` or matched by
+/// a `[[resolver_rule]]`.
+fn is_supported_directive(name: &str, config: &RendererConfig) -> bool {
+    SUPPORTED_DIRECTIVES.contains(&name)
+        || config
+            .directive_mapping
+            .iter()
+            .flatten()
+            .any(|mapping| mapping.directive == name)
+        || config
+            .resolver_rule
+            .iter()
+            .flatten()
+            .any(|rule| rule.directive.as_deref() == Some(name))
+}
+
+/// Warn about the directives applied in the schema that are neither supported nor mapped.
+fn warn_unsupported_directives(definitions: &Definitions, config: &RendererConfig) {
+    for (directive, element) in applied_directives(definitions) {
+        if !is_supported_directive(&directive.name, config) {
+            log::warn!(
+                "directive is not supported yet, @{} of {}",
+                directive.name,
+                element
+            );
+        }
     }
 }
 
-fn convert_enum_value(
-    enum_def: &AsyncGqlPositioned<async_gql_types::EnumValueDefinition>,
-) -> EnumValue {
-    let enum_def = enum_def.node.clone();
+/// The directives applied in the schema with the element each one is applied to, e.g. `User.name`.
+pub fn applied_directives(definitions: &Definitions) -> Vec<(&Directive, String)> {
+    let mut result = Vec::<(&Directive, String)>::new();
+    fn add<'a>(
+        result: &mut Vec<(&'a Directive, String)>,
+        directives: &'a [Directive],
+        element: String,
+    ) {
+        result.extend(
+            directives
+                .iter()
+                .map(|directive| (directive, element.clone())),
+        );
+    }
+    fn add_fields<'a>(
+        result: &mut Vec<(&'a Directive, String)>,
+        type_name: &str,
+        fields: &'a [Field],
+    ) {
+        for field in fields.iter() {
+            add(
+                result,
+                &field.directives,
+                format!("{}.{}", type_name, field.name),
+            );
+            for argument in field.arguments.iter() {
+                add(
+                    result,
+                    &argument.directives,
+                    format!("{}.{}({})", type_name, field.name, argument.name),
+                );
+            }
+        }
+    }
+    for object in definitions.objects.values() {
+        add(&mut result, &object.directives, object.name.to_string());
+        add_fields(&mut result, &object.name, &object.fields);
+    }
+    for interface in definitions.interfaces.values() {
+        add(
+            &mut result,
+            &interface.directives,
+            interface.name.to_string(),
+        );
+        add_fields(&mut result, &interface.name, &interface.fields);
+    }
+    for input_object in definitions.input_objects.values() {
+        add(
+            &mut result,
+            &input_object.directives,
+            input_object.name.to_string(),
+        );
+        for field in input_object.fields.iter() {
+            add(
+                &mut result,
+                &field.directives,
+                format!("{}.{}", input_object.name, field.name),
+            );
+        }
+    }
+    for enm in definitions.enums.values() {
+        add(&mut result, &enm.directives, enm.name.to_string());
+        for value in enm.values.iter() {
+            add(
+                &mut result,
+                &value.directives,
+                format!("{}.{}", enm.name, value.value_name),
+            );
+        }
+    }
+    for union in definitions.unions.values() {
+        add(&mut result, &union.directives, union.name.to_string());
+    }
+    for scalar in definitions.scalars.values() {
+        add(&mut result, &scalar.directives, scalar.name.to_string());
+    }
+    result
+}
+
+fn is_executable_location(location: &async_gql_types::DirectiveLocation) -> bool {
+    use async_gql_types::DirectiveLocation::*;
+    matches!(
+        location,
+        Query
+            | Mutation
+            | Subscription
+            | Field
+            | FragmentDefinition
+            | FragmentSpread
+            | InlineFragment
+            | VariableDefinition
+    )
+}
+
+/// The executable directives served on fields. The type system directives are read where they
+/// are applied instead.
+fn convert_directive_def(
+    directive_def: AsyncGqlPositioned<async_gql_types::DirectiveDefinition>,
+    config: &RendererConfig,
+) -> Option<DirectiveDefinition> {
+    let line_pos = directive_def.pos.line;
+    let directive_def = directive_def.node;
+    let name = node_as_string!(directive_def.name);
+    let (executable, type_system): (Vec<_>, Vec<_>) = directive_def
+        .locations
+        .iter()
+        .map(|location| location.node)
+        .partition(is_executable_location);
+
+    if executable.is_empty() {
+        if !is_supported_directive(&name, config) {
+            log::warn!("directive is not supported yet, @{}", name);
+        }
+        return None;
+    }
+    if !executable.contains(&async_gql_types::DirectiveLocation::Field) {
+        log::warn!(
+            "directive @{} is skipped. only the FIELD location is supported",
+            name
+        );
+        return None;
+    }
+    if executable.len() > 1 || !type_system.is_empty() {
+        log::warn!(
+            "directive @{} is served on FIELD only. the other locations are dropped",
+            name
+        );
+    }
+
+    Some(DirectiveDefinition {
+        arguments: directive_def
+            .arguments
+            .iter()
+            .map(convert_argument)
+            .collect(),
+        name,
+        description: directive_def.description.map(|desc| node_as_string!(desc)),
+        line_pos,
+        features: vec![],
+    })
+}
+
+pub fn convert_directives(
+    directives: &[AsyncGqlPositioned<async_gql_types::ConstDirective>],
+) -> Vec<Directive> {
+    directives
+        .iter()
+        .map(|directive| {
+            let name = node_as_string!(directive.node.name);
+            Directive {
+                name,
+                arguments: directive
+                    .node
+                    .arguments
+                    .iter()
+                    .map(|(name, value)| (node_as_string!(name), value.node.clone()))
+                    .collect(),
+            }
+        })
+        .collect()
+}
 
-    if !enum_def.directives.is_empty() {
+/// `@cacheControl(maxAge: 60, scope: PRIVATE)`. `inheritMaxAge` has no counterpart in async-graphql.
+fn cache_control(directives: &[Directive], element: &str) -> Option<CacheControl> {
+    let directive = find_directive(directives, CACHE_CONTROL_DIRECTIVE)?;
+    let max_age = match directive.argument("maxAge") {
+        None => None,
+        Some(async_graphql::Value::Number(max_age)) if max_age.is_u64() => max_age.as_u64(),
+        Some(max_age) => {
+            log::warn!("invalid maxAge {} of @cacheControl of {}", max_age, element);
+            None
+        }
+    };
+    let private = match directive.argument("scope") {
+        None => false,
+        Some(async_graphql::Value::Enum(scope)) if scope.as_str() == "PRIVATE" => true,
+        Some(async_graphql::Value::Enum(scope)) if scope.as_str() == "PUBLIC" => false,
+        Some(scope) => {
+            log::warn!("invalid scope {} of @cacheControl of {}", scope, element);
+            false
+        }
+    };
+    if directive.argument("inheritMaxAge").is_some() {
         log::warn!(
-            "directive is not supported yet, {}",
-            node_as_string!(enum_def.value)
+            "inheritMaxAge of @cacheControl of {} is not supported",
+            element
         );
     }
+    if max_age.is_none() && !private {
+        return None;
+    }
+    Some(CacheControl { max_age, private })
+}
+
+fn convert_enum_value(
+    enum_def: &AsyncGqlPositioned<async_gql_types::EnumValueDefinition>,
+) -> EnumValue {
+    let enum_def = enum_def.node.clone();
 
+    let value_name = node_as_string!(enum_def.value);
     EnumValue {
-        value_name: node_as_string!(enum_def.value),
+        directives: convert_directives(&enum_def.directives),
+        value_name,
         description: enum_def.description.map(|desc| node_as_string!(desc)),
+        visible: None,
     }
 }
 
@@ -193,7 +712,7 @@ fn convert_fields(
     fields: &Vec<AsyncGqlPositioned<async_gql_types::FieldDefinition>>,
     fields_setting: Option<&FieldsSetting>,
     fields_resolver_setting: Option<&FieldsResolverSetting>,
-) -> Vec<Field> {
+) -> Result<Vec<Field>> {
     fields
         .iter()
         .map(|field| convert_object_field_def(field, fields_setting, fields_resolver_setting))
@@ -207,9 +726,13 @@ fn maybe_replace_field(
     if let Some(fields_settings) = fields_settings {
         if let Some(fields_setting) = fields_settings.get(field_name) {
             if let Some(replace_field_type) = &fields_setting.replace_field_type {
-                let repalced_type = async_gql_types::Type::new(&replace_field_type).ok_or(
-                    anyhow!("invalid replace field type: {}", replace_field_type),
-                )?;
+                let repalced_type =
+                    async_gql_types::Type::new(replace_field_type).ok_or_else(|| {
+                        Error::config_entry(
+                            fields_setting.entry_name(),
+                            format!("invalid replace_field_type: {}", replace_field_type),
+                        )
+                    })?;
                 return Ok(Some(repalced_type));
             }
         }
@@ -217,20 +740,27 @@ fn maybe_replace_field(
     Ok(None)
 }
 
+fn primitive_type_of_field(
+    field_name: &str,
+    fields_settings: Option<&FieldsSetting>,
+) -> Option<FieldPrimitiveType> {
+    let fields_setting = fields_settings?.get(field_name)?;
+    let rust_type = fields_setting.primitive_type.as_ref()?;
+    Some(FieldPrimitiveType {
+        rust_type: rust_type.to_string(),
+        entry: fields_setting.entry_name(),
+    })
+}
+
 fn convert_object_field_def(
     field_def: &AsyncGqlPositioned<async_gql_types::FieldDefinition>,
     fields_setting: Option<&FieldsSetting>,
     fields_resolver_setting: Option<&FieldsResolverSetting>,
-) -> Field {
+) -> Result<Field> {
     let line_pos = field_def.pos.line;
     let field_def = field_def.node.clone();
 
-    if !field_def.directives.is_empty() {
-        log::warn!(
-            "directive is not supported yet, {}",
-            node_as_string!(field_def.name)
-        );
-    }
+    let directives = convert_directives(&field_def.directives);
 
     let mut arguments: Vec<Argument> = field_def
         .arguments
@@ -243,48 +773,59 @@ fn convert_object_field_def(
             if let Some(args) = &resolver_setting.argument {
                 let mut additional_args: Vec<Argument> = args
                     .iter()
-                    .map(|arg| convert_argument_from_config_arg(arg))
-                    .collect();
+                    .map(|arg| convert_argument_from_config_arg(arg, resolver_setting))
+                    .collect::<Result<Vec<Argument>>>()?;
                 arguments.append(&mut additional_args);
             }
         }
     }
 
-    let field_type = match maybe_replace_field(field_name, fields_setting).unwrap() {
+    let field_type = match maybe_replace_field(field_name, fields_setting)? {
         Some(replaced_field) => replaced_field,
         None => field_def.ty.node,
     };
 
-    Field {
+    Ok(Field {
         name: node_as_string!(field_def.name),
         description: field_def.description.map(|desc| node_as_string!(desc)),
         typ: convert_type_to_value(field_type),
         arguments,
         line_pos,
-    }
+        primitive_type: primitive_type_of_field(field_name, fields_setting),
+        cache_control: cache_control(&directives, field_name),
+        directives,
+        visible: None,
+        features: vec![],
+    })
 }
 
 pub fn convert_input_field_def(
     input_field_def: &AsyncGqlPositioned<async_gql_types::InputValueDefinition>,
     fields_setting: Option<&FieldsSetting>,
-) -> InputField {
+) -> Result<InputField> {
     let line_pos = input_field_def.pos.line;
     let input_field_def = input_field_def.node.clone();
 
     let field_name = node_as_string!(input_field_def.name);
-    let field_type = match maybe_replace_field(&field_name, fields_setting).unwrap() {
+    let field_type = match maybe_replace_field(&field_name, fields_setting)? {
         Some(replaced_field) => replaced_field,
         None => input_field_def.ty.node,
     };
 
-    InputField {
+    Ok(InputField {
         name: node_as_string!(input_field_def.name),
         description: input_field_def
             .description
             .map(|desc| node_as_string!(desc)),
         typ: convert_type_to_value(field_type),
         line_pos,
-    }
+        primitive_type: primitive_type_of_field(&field_name, fields_setting),
+        maybe_undefined: false,
+        directives: convert_directives(&input_field_def.directives),
+        visible: None,
+        features: vec![],
+        secret: false,
+    })
 }
 
 fn convert_argument(
@@ -299,29 +840,39 @@ fn convert_argument(
         );
     }
 
-    if !input_def.directives.is_empty() {
-        log::warn!(
-            "directive is not supported yet. argument:{}",
-            node_as_string!(input_def.name)
-        );
-    }
-
+    let name = node_as_string!(input_def.name);
     Argument {
-        name: node_as_string!(input_def.name),
+        directives: convert_directives(&input_def.directives),
+        name,
         typ: convert_type_to_value(input_def.ty.node),
         description: input_def.description.map(|desc| node_as_string!(desc)),
+        maybe_undefined: false,
+        secret: false,
     }
 }
 
-fn convert_argument_from_config_arg(arg: &ResolverArgument) -> Argument {
-    let typ = async_gql_types::Type::new(&arg.arg_type)
-        .unwrap_or_else(|| panic!("invalid resolver argument type :{:?}", arg));
-
-    Argument {
+fn convert_argument_from_config_arg(
+    arg: &ResolverArgument,
+    resolver_setting: &ResolverSetting,
+) -> Result<Argument> {
+    let typ = async_gql_types::Type::new(&arg.arg_type).ok_or_else(|| {
+        Error::config_entry(
+            resolver_setting.entry_name(),
+            format!(
+                "invalid arg_type {} of argument {}",
+                arg.arg_type, arg.arg_name
+            ),
+        )
+    })?;
+
+    Ok(Argument {
         name: arg.arg_name.clone(),
         typ: convert_type_to_value(typ),
         description: arg.arg_description.clone(),
-    }
+        maybe_undefined: false,
+        directives: vec![],
+        secret: false,
+    })
 }
 
 pub fn convert_type_to_value(type_def: async_gql_types::Type) -> ValueTypeDef {
@@ -340,3 +891,29 @@ pub fn convert_type_to_value(type_def: async_gql_types::Type) -> ValueTypeDef {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    pub fn support_directives_of_mappings_and_rules() {
+        let config: RendererConfig = toml::from_str(
+            r##"
+            [[directive_mapping]]
+            directive = "rateLimit"
+            attribute = "#[rate_limit]"
+
+            [[resolver_rule]]
+            directive = "loader"
+            resolver_type = "method"
+            "##,
+        )
+        .unwrap();
+        assert!(is_supported_directive(CACHE_CONTROL_DIRECTIVE, &config));
+        assert!(is_supported_directive("rateLimit", &config));
+        assert!(is_supported_directive("loader", &config));
+        assert!(!is_supported_directive("model", &config));
+    }
+}
@@ -1,4 +1,5 @@
-use anyhow::{anyhow, Result};
+use crate::config::{InlineSettings, Visible};
+use crate::error::{Error, Result};
 use derive_macro_tool::{LinePosition, NameString};
 use lazy_static::lazy_static;
 use paste::paste;
@@ -6,12 +7,14 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 use strum::{AsRefStr, EnumString};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct StructuredSchema {
     pub query_name: Option<String>,
     pub mutation_name: Option<String>,
     pub subscription_name: Option<String>,
     pub definitions: Definitions,
+    /// The `@reverse` settings read from the schema, applied with `RendererConfig::with_inline_settings`.
+    pub inline_settings: InlineSettings,
 }
 
 impl StructuredSchema {
@@ -39,7 +42,7 @@ pub enum Definition {
     InputObject(InputObject),
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Definitions {
     pub input_objects: HashMap<String, InputObject>,
     pub objects: HashMap<String, Object>,
@@ -47,9 +50,34 @@ pub struct Definitions {
     pub unions: HashMap<String, Union>,
     pub enums: HashMap<String, Enum>,
     pub interfaces: HashMap<String, Interface>,
+    /// Types of the schema that `[[type_mapping]]` replaces with an existing rust type.
+    pub mapped_types: HashMap<String, MappedType>,
+    pub directives: HashMap<String, DirectiveDefinition>,
 }
 
 impl Definitions {
+    /// Replace the definition named `name` with the mapped rust type, or the previous mapping of it.
+    /// Returns false if the schema does not define the type.
+    pub fn map_type(&mut self, name: &str, mut mapped_type: MappedType) -> bool {
+        if let Some(previous) = self.mapped_types.get(name) {
+            mapped_type.impl_interface_name = previous.impl_interface_name.clone();
+        } else if let Some(object) = self.objects.remove(name) {
+            mapped_type.impl_interface_name = object.impl_interface_name;
+        } else {
+            let defined = self.scalars.remove(name).is_some()
+                || self.enums.remove(name).is_some()
+                || self.input_objects.remove(name).is_some()
+                || self.unions.remove(name).is_some()
+                || self.interfaces.remove(name).is_some()
+                || is_preserverd_type(name);
+            if !defined {
+                return false;
+            }
+        }
+        self.mapped_types.insert(name.to_string(), mapped_type);
+        true
+    }
+
     pub fn add_definition(&mut self, definition: Definition) {
         match definition {
             Definition::Scalar(v) => {
@@ -85,6 +113,8 @@ impl Default for Definitions {
             unions: HashMap::<String, Union>::new(),
             enums: HashMap::<String, Enum>::new(),
             interfaces: HashMap::<String, Interface>::new(),
+            mapped_types: HashMap::<String, MappedType>::new(),
+            directives: HashMap::<String, DirectiveDefinition>::new(),
         }
     }
 }
@@ -97,36 +127,97 @@ pub trait LinePosition {
     fn line_position(&self) -> usize;
 }
 
-#[derive(Debug, NameString, LinePosition, PartialEq)]
+/// A directive applied in the schema, e.g. `@internal` or `@cacheControl(maxAge: 60)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Directive {
+    pub name: String,
+    pub arguments: Vec<(String, async_graphql::Value)>,
+}
+
+impl Directive {
+    pub fn argument(&self, name: &str) -> Option<&async_graphql::Value> {
+        self.arguments
+            .iter()
+            .find(|(argument_name, _)| argument_name == name)
+            .map(|(_, value)| value)
+    }
+}
+
+/// An executable directive defined in the schema, e.g. `directive @upper(prefix: String) on FIELD`.
+/// async-graphql serves custom directives on fields only, so the other locations are dropped.
+#[derive(Debug, Clone, NameString, LinePosition, PartialEq)]
+pub struct DirectiveDefinition {
+    pub name: String,
+    pub description: Option<String>,
+    pub arguments: Vec<Argument>,
+    pub line_pos: usize,
+    /// The features of the types the arguments refer to.
+    pub features: Vec<String>,
+}
+
+pub fn find_directive<'a>(directives: &'a [Directive], name: &str) -> Option<&'a Directive> {
+    directives.iter().find(|directive| directive.name == name)
+}
+
+#[derive(Debug, Clone, NameString, LinePosition, PartialEq)]
 pub struct Scalar {
     pub name: String,
     pub line_pos: usize,
+    pub directives: Vec<Directive>,
+    pub visible: Option<Visible>,
+    /// The cargo features the type is gated by, `[[type]] feature`.
+    pub features: Vec<String>,
+    /// The `url` of `@specifiedBy`.
+    pub specified_by_url: Option<String>,
+    /// The object of a typed ID, see `parse::typed_ids`.
+    pub id_of: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MappedType {
+    pub name: String,
+    pub rust_type: String,
+    pub using: Option<String>,
+    /// The config entry of the mapping, to name it in errors.
+    pub entry_name: String,
+    /// The interfaces of the schema the mapped object implements, so it stays one of their members.
+    pub impl_interface_name: Vec<String>,
 }
 
-#[derive(Debug, NameString, LinePosition, PartialEq)]
+#[derive(Debug, Clone, NameString, LinePosition, PartialEq)]
 pub struct Enum {
     pub name: String,
     pub values: Vec<EnumValue>,
     pub line_pos: usize,
     pub description: Option<String>,
+    pub directives: Vec<Directive>,
+    pub visible: Option<Visible>,
+    /// The cargo features the type is gated by, `[[type]] feature`.
+    pub features: Vec<String>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct EnumValue {
     pub value_name: String,
     pub description: Option<String>,
+    pub directives: Vec<Directive>,
+    pub visible: Option<Visible>,
 }
 
-#[derive(Debug, NameString, LinePosition, PartialEq)]
+#[derive(Debug, Clone, NameString, LinePosition, PartialEq)]
 pub struct Union {
     pub name: String,
     //TODO() rename to concrete_type_names
     pub type_names: Vec<String>,
     pub line_pos: usize,
     pub description: Option<String>,
+    pub directives: Vec<Directive>,
+    pub visible: Option<Visible>,
+    /// The cargo features the type is gated by, `[[type]] feature`.
+    pub features: Vec<String>,
 }
 
-#[derive(Debug, NameString, LinePosition, PartialEq)]
+#[derive(Debug, Clone, NameString, LinePosition, PartialEq)]
 pub struct Interface {
     pub name: String,
     //TODO(tacogips) concrete_type_names  always be empty?
@@ -134,52 +225,100 @@ pub struct Interface {
     pub fields: Vec<Field>,
     pub description: Option<String>,
     pub line_pos: usize,
+    pub directives: Vec<Directive>,
+    pub visible: Option<Visible>,
+    /// The cargo features the type is gated by, `[[type]] feature`.
+    pub features: Vec<String>,
 }
 
-#[derive(Debug, NameString, LinePosition, PartialEq)]
+#[derive(Debug, Clone, NameString, LinePosition, PartialEq)]
 pub struct InputObject {
     pub name: String,
     pub fields: Vec<InputField>,
     pub description: Option<String>,
     pub line_pos: usize,
+    pub directives: Vec<Directive>,
+    pub visible: Option<Visible>,
+    /// The cargo features the type is gated by, `[[type]] feature`.
+    pub features: Vec<String>,
 }
 
-#[derive(Debug, NameString, LinePosition, PartialEq)]
+#[derive(Debug, Clone, NameString, LinePosition, PartialEq)]
 pub struct Object {
     pub name: String,
     pub fields: Vec<Field>,
     pub description: Option<String>,
     pub line_pos: usize,
     pub impl_interface_name: Vec<String>,
+    pub directives: Vec<Directive>,
+    pub visible: Option<Visible>,
+    /// The cargo features the type is gated by, `[[type]] feature`.
+    pub features: Vec<String>,
+    pub cache_control: Option<CacheControl>,
+}
+
+/// `@cacheControl` of an object or a field.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheControl {
+    pub max_age: Option<u64>,
+    pub private: bool,
 }
 
-#[derive(Debug, NameString, LinePosition, PartialEq)]
+/// `[[field]] primitive_type`, the rust type of an `Int`, `Float` or `ID` field.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldPrimitiveType {
+    pub rust_type: String,
+    /// The entry of the config that sets it, see `FieldSetting::entry_name`.
+    pub entry: String,
+}
+
+#[derive(Debug, Clone, NameString, LinePosition, PartialEq)]
 pub struct Field {
     pub name: String,
     pub description: Option<String>,
     pub typ: ValueTypeDef,
     pub arguments: Vec<Argument>,
     pub line_pos: usize,
+    pub primitive_type: Option<FieldPrimitiveType>,
+    pub directives: Vec<Directive>,
+    pub visible: Option<Visible>,
+    /// The features of the types the field refers to, other than those of its parent.
+    pub features: Vec<String>,
+    pub cache_control: Option<CacheControl>,
 }
 
-#[derive(Debug, NameString, PartialEq)]
+#[derive(Debug, Clone, NameString, PartialEq)]
 pub struct Argument {
     pub name: String,
     pub typ: ValueTypeDef,
     pub description: Option<String>,
     //TODO(tacogips) default value not supported
     //pub default_value: Option<String>,
+    /// Rendered as `MaybeUndefined<T>` when nullable.
+    pub maybe_undefined: bool,
+    pub directives: Vec<Directive>,
+    /// Rendered with `#[graphql(secret)]`.
+    pub secret: bool,
 }
 
-#[derive(Debug, NameString, LinePosition, PartialEq)]
+#[derive(Debug, Clone, NameString, LinePosition, PartialEq)]
 pub struct InputField {
     pub name: String,
     pub description: Option<String>,
     pub typ: ValueTypeDef,
     pub line_pos: usize,
+    pub primitive_type: Option<FieldPrimitiveType>,
+    /// Rendered as `MaybeUndefined<T>` when nullable.
+    pub maybe_undefined: bool,
+    pub directives: Vec<Directive>,
+    pub visible: Option<Visible>,
+    /// The features of the types the field refers to, other than those of its parent.
+    pub features: Vec<String>,
+    /// Rendered with `#[graphql(secret)]`.
+    pub secret: bool,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ValueTypeDef {
     Named(NamedValue),
     List(ListValue),
@@ -193,15 +332,27 @@ impl ValueTypeDef {
         }
     }
 
+    pub fn is_list(&self) -> bool {
+        matches!(self, ValueTypeDef::List(_))
+    }
+
     pub fn element_value_type_def<'a>(&self, definitions: &'a Definitions) -> Result<TypeDef<'a>> {
         match self {
             ValueTypeDef::Named(v) => v.as_type_def(&definitions),
             ValueTypeDef::List(v) => (*v.inner).element_value_type_def(&definitions),
         }
     }
+
+    /// The name of the type, or of the type of the elements for a list.
+    pub fn element_type_name(&self) -> &str {
+        match self {
+            ValueTypeDef::Named(v) => &v.value_type_name,
+            ValueTypeDef::List(v) => v.inner.element_type_name(),
+        }
+    }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct NamedValue {
     pub value_type_name: String,
     pub is_nullable: bool,
@@ -211,7 +362,9 @@ impl NamedValue {
     pub fn as_type_def<'a>(&self, definitions: &'a Definitions) -> Result<TypeDef<'a>> {
         let type_name = &self.value_type_name;
         //TODO(tacogips) what about in the case that object and input_object has same name?
-        let result = if let Some(primitive) = PRIMITIVE_KIND_MAP.get(type_name.as_str()) {
+        let result = if let Some(mapped_type) = definitions.mapped_types.get(type_name) {
+            TypeDef::Mapped(mapped_type)
+        } else if let Some(primitive) = PRIMITIVE_KIND_MAP.get(type_name.as_str()) {
             TypeDef::Primitive(primitive)
         } else if let Some(input_object) = definitions.input_objects.get(type_name) {
             TypeDef::InputObject(input_object)
@@ -229,7 +382,7 @@ impl NamedValue {
             if is_preserverd_type(type_name) {
                 TypeDef::AsyncGraphqlPreserved(type_name.clone())
             } else {
-                return Err(anyhow!("type: {} not defined", type_name));
+                return Err(Error::UndefinedType(type_name.clone()));
             }
         };
 
@@ -237,7 +390,7 @@ impl NamedValue {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ListValue {
     pub inner: Box<ValueTypeDef>,
     pub is_nullable: bool,
@@ -267,6 +420,9 @@ pub enum TypeDef<'a> {
     Union(&'a Union),
     Interface(&'a Interface),
     AsyncGraphqlPreserved(String),
+    Mapped(&'a MappedType),
+    /// Only the parent of the arguments of a directive, never the type of a value.
+    Directive(&'a DirectiveDefinition),
 }
 impl<'a> TypeDef<'a> {
     is! {Primitive}
@@ -276,6 +432,7 @@ impl<'a> TypeDef<'a> {
     is! {Scalar}
     is! {Union}
     is! {Interface}
+    is! {Mapped}
 
     pub fn name(&self) -> String {
         match self {
@@ -287,6 +444,8 @@ impl<'a> TypeDef<'a> {
             TypeDef::Union(v) => v.name.to_string(),
             TypeDef::Interface(v) => v.name.to_string(),
             TypeDef::AsyncGraphqlPreserved(name) => name.clone(),
+            TypeDef::Mapped(v) => v.name.to_string(),
+            TypeDef::Directive(v) => v.name.to_string(),
         }
     }
 }
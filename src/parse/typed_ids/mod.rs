@@ -0,0 +1,218 @@
+//! Typed IDs. With `typed_ids` every object with an `id: ID` field gets a `UserId(ID)` newtype, a
+//! scalar that is still called `ID` in the schema. An `ID` is tied to the object `T` and typed as
+//! `TId` when it is
+//!
+//! - the `id` field of `T`, unless an interface of `T` declares `id`,
+//! - a field or argument named `tId` or `t_id`, or `tIds` or `t_ids` for a list,
+//! - the `id` (`ids`) argument of a field returning `T`,
+//! - set by `[[field]] id_of` or `argument_id_of`, which also create the newtype without `typed_ids`.
+//!
+//! A field with `[[field]] primitive_type` is only typed by `id_of`.
+
+use super::structured::*;
+use crate::config::{FieldsSetting, RendererConfig};
+use heck::CamelCase;
+use std::collections::{HashMap, HashSet};
+
+const ID: &str = "ID";
+
+pub fn typed_id_name(object_name: &str) -> String {
+    format!("{}Id", object_name)
+}
+
+pub fn add_typed_ids(definitions: &mut Definitions, config: &RendererConfig) {
+    let mut owners: Vec<String> = vec![];
+    if config.typed_ids {
+        for object in definitions.objects.values() {
+            if object.fields.iter().any(|field| {
+                field.name == "id"
+                    && matches!(&field.typ, ValueTypeDef::Named(named) if named.value_type_name == ID)
+            }) {
+                owners.push(object.name.to_string());
+            }
+        }
+    }
+    for field_setting in config.field.iter().flatten() {
+        let argument_owners = field_setting
+            .argument_id_of
+            .iter()
+            .flat_map(|argument_id_of| argument_id_of.values());
+        for owner in field_setting.id_of.iter().chain(argument_owners) {
+            if definitions.objects.contains_key(owner) {
+                owners.push(owner.to_string());
+            }
+        }
+    }
+    owners.sort();
+    owners.dedup();
+
+    // object name -> typed id name
+    let mut typed_ids = HashMap::<String, String>::new();
+    for owner in owners {
+        let name = typed_id_name(&owner);
+        if is_defined(definitions, &name) {
+            log::warn!(
+                "{} is defined in the schema, the ids of {} are not typed",
+                name,
+                owner
+            );
+            continue;
+        }
+        typed_ids.insert(owner, name);
+    }
+    if typed_ids.is_empty() {
+        return;
+    }
+
+    let interfaces_with_id: HashSet<String> = definitions
+        .interfaces
+        .values()
+        .filter(|interface| interface.fields.iter().any(|field| field.name == "id"))
+        .map(|interface| interface.name.to_string())
+        .collect();
+    let field_settings = config.field_setting();
+
+    for object in definitions.objects.values_mut() {
+        let id_owner = if object
+            .impl_interface_name
+            .iter()
+            .any(|name| interfaces_with_id.contains(name))
+        {
+            None
+        } else {
+            typed_ids.get(&object.name)
+        };
+        let fields_setting = field_settings.get(&object.name);
+        for field in object.fields.iter_mut() {
+            let by_id_field = if field.name == "id" { id_owner } else { None };
+            type_field(field, by_id_field, fields_setting, &typed_ids);
+        }
+    }
+    for interface in definitions.interfaces.values_mut() {
+        let fields_setting = field_settings.get(&interface.name);
+        for field in interface.fields.iter_mut() {
+            type_field(field, None, fields_setting, &typed_ids);
+        }
+    }
+    for input_object in definitions.input_objects.values_mut() {
+        let fields_setting = field_settings.get(&input_object.name);
+        for field in input_object.fields.iter_mut() {
+            let explicit = explicit_owner(&field.name, fields_setting, &typed_ids);
+            if let Some(typed_id) = explicit.or_else(|| {
+                if field.primitive_type.is_some() {
+                    None
+                } else {
+                    owner_by_name(&field.name, &field.typ, &typed_ids)
+                }
+            }) {
+                retype(&mut field.typ, typed_id);
+            }
+        }
+    }
+
+    let scalars: Vec<Scalar> = typed_ids
+        .iter()
+        .map(|(owner, name)| Scalar {
+            name: name.to_string(),
+            line_pos: definitions
+                .objects
+                .get(owner)
+                .map_or(0, |object| object.line_pos),
+            specified_by_url: None,
+            id_of: Some(owner.to_string()),
+            directives: vec![],
+            visible: None,
+            features: vec![],
+        })
+        .collect();
+    for scalar in scalars {
+        definitions.add_definition(Definition::Scalar(scalar));
+    }
+}
+
+fn type_field(
+    field: &mut Field,
+    by_id_field: Option<&String>,
+    fields_setting: Option<&FieldsSetting>,
+    typed_ids: &HashMap<String, String>,
+) {
+    let typed_id = explicit_owner(&field.name, fields_setting, typed_ids).or_else(|| {
+        if field.primitive_type.is_some() {
+            None
+        } else {
+            by_id_field.or_else(|| owner_by_name(&field.name, &field.typ, typed_ids))
+        }
+    });
+    if let Some(typed_id) = typed_id {
+        retype(&mut field.typ, typed_id);
+    }
+
+    let argument_id_of = fields_setting
+        .and_then(|fields_setting| fields_setting.get(&field.name))
+        .and_then(|field_setting| field_setting.argument_id_of.as_ref());
+    let returned = typed_ids.get(field.typ.element_type_name());
+    for argument in field.arguments.iter_mut() {
+        let explicit = argument_id_of
+            .and_then(|argument_id_of| argument_id_of.get(&argument.name))
+            .and_then(|owner| typed_ids.get(owner));
+        let by_returned = match argument.name.as_str() {
+            "id" | "ids" => returned,
+            _ => None,
+        };
+        if let Some(typed_id) = explicit
+            .or(by_returned)
+            .or_else(|| owner_by_name(&argument.name, &argument.typ, typed_ids))
+        {
+            retype(&mut argument.typ, typed_id);
+        }
+    }
+}
+
+fn explicit_owner<'a>(
+    field_name: &str,
+    fields_setting: Option<&FieldsSetting>,
+    typed_ids: &'a HashMap<String, String>,
+) -> Option<&'a String> {
+    fields_setting
+        .and_then(|fields_setting| fields_setting.get(field_name))
+        .and_then(|field_setting| field_setting.id_of.as_ref())
+        .and_then(|owner| typed_ids.get(owner))
+}
+
+/// `userId: ID` and `user_id: ID` are ids of `User`, `userIds: [ID]` a list of them.
+fn owner_by_name<'a>(
+    name: &str,
+    typ: &ValueTypeDef,
+    typed_ids: &'a HashMap<String, String>,
+) -> Option<&'a String> {
+    let is_list = match typ {
+        ValueTypeDef::Named(_) => false,
+        ValueTypeDef::List(_) => true,
+    };
+    let suffix = if is_list { "Ids" } else { "Id" };
+    let camel_name = name.to_camel_case();
+    let owner = camel_name.strip_suffix(suffix)?;
+    typed_ids.get(owner)
+}
+
+/// Replace the `ID` of the type with `typed_id`, keeping the nullability and the list.
+fn retype(typ: &mut ValueTypeDef, typed_id: &str) {
+    match typ {
+        ValueTypeDef::Named(named) => {
+            if named.value_type_name == ID {
+                named.value_type_name = typed_id.to_string();
+            }
+        }
+        ValueTypeDef::List(list) => retype(&mut list.inner, typed_id),
+    }
+}
+
+fn is_defined(definitions: &Definitions, name: &str) -> bool {
+    definitions.objects.contains_key(name)
+        || definitions.input_objects.contains_key(name)
+        || definitions.scalars.contains_key(name)
+        || definitions.unions.contains_key(name)
+        || definitions.enums.contains_key(name)
+        || definitions.interfaces.contains_key(name)
+        || definitions.mapped_types.contains_key(name)
+}
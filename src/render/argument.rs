@@ -1,9 +1,10 @@
 use super::super::parse::{self, *};
 use super::utils::SnakeCaseWithUnderscores;
-use anyhow::Result;
+use crate::error::Result;
 use proc_macro2::TokenStream;
 use quote::*;
 
+use super::tokens::{directive_attributes_token, merge_graphql_attributes};
 use super::typ::*;
 use super::RenderContext;
 
@@ -11,6 +12,7 @@ pub fn argument_def_token(
     argument: &parse::Argument,
     schema: &StructuredSchema,
     name_prefix: &str,
+    with_attributes: bool,
     render_context: &RenderContext,
 ) -> Result<TokenStream> {
     let name = format_ident!(
@@ -18,9 +20,32 @@ pub fn argument_def_token(
         name_prefix,
         argument.name_string().to_snake_case_with_underscores()
     );
-    let typ = value_type_def_token(&argument.typ, &schema, &render_context)?;
+    let typ = input_value_type_token(
+        &argument.typ,
+        None,
+        argument.maybe_undefined,
+        schema,
+        render_context,
+    )?;
 
-    let result = quote! { #name:#typ };
+    let attribute = if with_attributes {
+        let secret = if argument.secret {
+            vec![quote! { secret }]
+        } else {
+            vec![]
+        };
+        let directive_attributes =
+            directive_attributes_token(&argument.directives, render_context.config)?;
+        merge_graphql_attributes(
+            directive_attributes,
+            secret,
+            &format!("argument {}", argument.name),
+        )?
+    } else {
+        quote! {}
+    };
+
+    let result = quote! { #attribute #name:#typ };
 
     Ok(result)
 }
@@ -6,6 +6,10 @@ pub const FILE_HEADER_COMMENT: &str = r#"
 // This file was generated by https://github.com/tacogips/async-graphql-reverse
 "#;
 
+pub const CUSTOM_DIRECTIVES_COMMENT: &str = r#"
+// The CustomDirective impls below use #[async_trait::async_trait], so the crate needs `async-trait` in its dependencies
+"#;
+
 pub fn to_rust_docs_token(comment: &str) -> TokenStream {
     let comments: Vec<TokenStream> = comment
         .split("\n")
@@ -0,0 +1,279 @@
+use super::super::parse::*;
+use super::fields::{field_is_method_or_member, ResolverType};
+use super::sorter::sort_by_line_pos_and_name;
+use super::RenderContext;
+use crate::config::{scalar_preset_names, Phase, RendererConfig};
+use crate::error::{Result, ResultExt, Stage};
+use heck::CamelCase;
+
+/// A commented starter `reverse.toml` for the schema. It lists a `[[resolver]]` for every object field
+/// that is rendered as a resolver method by default, an `[[enum]]` for every enum and every other key
+/// with its default value.
+pub fn config_template(schema: &StructuredSchema) -> Result<String> {
+    let defaults = RendererConfig::default();
+    let mut lines: Vec<String> = vec![
+        "# Generated by `async-graphql-reverse init`.".to_string(),
+        "".to_string(),
+        "# Written at the top of objects.rs. Defaults to importing async_graphql::* and module_path.data_source."
+            .to_string(),
+        format!("# header = {}", toml_string(&defaults.header())),
+        "".to_string(),
+        "# The expression the resolver methods take the DataSource from.".to_string(),
+        format!(
+            "data_source_fetch_method = {}",
+            toml_string(&defaults.data_source_fetch_method)
+        ),
+        "".to_string(),
+        "# \"method\" or \"field\". Overrides how every field is rendered.".to_string(),
+        "# resolver_type = \"method\"".to_string(),
+        "".to_string(),
+        "# Set resolver_type and attribute of the fields matching a rule, tried in order before".to_string(),
+        "# resolver_type. parent_type and field are globs, or regexes between slashes.".to_string(),
+        "# [[resolver_rule]]".to_string(),
+        "# parent_type = \"*Connection\"".to_string(),
+        "# return_kind = \"object\"".to_string(),
+        "# list = true".to_string(),
+        "# resolver_type = \"method\"".to_string(),
+        "".to_string(),
+        "# Added to #[derive(..)] of every object.".to_string(),
+        "# additional_attributes = \"Default\"".to_string(),
+        "".to_string(),
+        format!(
+            "# Map well-known scalars to the types async-graphql implements them for: {}.",
+            scalar_preset_names().join(", ")
+        ),
+        "scalar_presets = []".to_string(),
+        "".to_string(),
+        "# The fields of these types are struct members instead of resolver methods.".to_string(),
+        "custom_member_types = []".to_string(),
+        "".to_string(),
+        "# rename_items of every enum that has none in its [[enum]].".to_string(),
+        "# enum_rename_items = \"camelCase\"".to_string(),
+        "".to_string(),
+        "# Only run these of objects, input_objects, unions, scalars, interfaces, enums and directives. Empty runs all."
+            .to_string(),
+        "phases = []".to_string(),
+        "".to_string(),
+        format!("no_object_impl = {}", defaults.no_object_impl),
+        format!("no_dependency_imports = {}", defaults.no_dependency_imports),
+        "".to_string(),
+        "# Generate a UserId(ID) newtype for the id of every object and use it for the fields and".to_string(),
+        "# arguments tied to the object, e.g. userId: ID!. [[field]] id_of ties any other ID.".to_string(),
+        format!("typed_ids = {}", defaults.typed_ids),
+        "".to_string(),
+        "# Render nullable input fields and arguments as MaybeUndefined<T> to tell an omitted value from null,".to_string(),
+        "# for the whole schema or for the input objects and the arguments of the objects listed.".to_string(),
+        format!("maybe_undefined = {}", defaults.maybe_undefined),
+        "maybe_undefined_types = []".to_string(),
+        "".to_string(),
+        "# Generate a variant of the schema by @tag(name: ..). Only the fields, input fields and enum values".to_string(),
+        "# tagged with one of include_tags, or whose type is, are kept, and everything tagged with one of".to_string(),
+        "# exclude_tags is removed. --include-tags and --exclude-tags override them.".to_string(),
+        "include_tags = []".to_string(),
+        "exclude_tags = []".to_string(),
+        "".to_string(),
+        "# Fail instead of warning when this config does not match the schema.".to_string(),
+        format!("strict = {}", defaults.strict),
+        "".to_string(),
+        "# Where the generated code imports the types of each category from. Point a category at".to_string(),
+        "# another crate and remove it from `phases` to share the types.".to_string(),
+        "[module_path]".to_string(),
+    ];
+    for phase in Phase::all() {
+        lines.push(format!(
+            "{} = {}",
+            phase.module_name(),
+            toml_string(defaults.module_path.of(&phase))
+        ));
+    }
+    lines.append(&mut vec![
+        format!(
+            "data_source = {}",
+            toml_string(&defaults.module_path.data_source)
+        ),
+        "".to_string(),
+        "# The rust types of Int, Float and ID. [[field]] primitive_type overrides them per field.".to_string(),
+        "[primitive]".to_string(),
+        "# int = \"i64\"".to_string(),
+        "# float = \"f64\"".to_string(),
+        "# id = \"ID\"".to_string(),
+        "".to_string(),
+        "# Use declarations added to the generated modules, keyed by objects, input_objects, unions,".to_string(),
+        "# scalars, interfaces, enums, datasource or all. Separate several declarations with `;`.".to_string(),
+        "# [using]".to_string(),
+        "# all = \"use chrono::{DateTime, Utc}\"".to_string(),
+        "".to_string(),
+    ]);
+
+    let mut objects: Vec<&Object> = schema.definitions.objects.values().collect();
+    objects.sort_by(sort_by_line_pos_and_name);
+    let custom_member_types = defaults.custom_member_types();
+    for object in objects {
+        let context = RenderContext {
+            parent: TypeDef::Object(object),
+            config: &defaults,
+        };
+        let mut fields: Vec<&Field> = object.fields.iter().collect();
+        fields.sort_by(sort_by_line_pos_and_name);
+        for field in fields {
+            let resolver_type = field_is_method_or_member(
+                field,
+                schema,
+                &context,
+                &defaults,
+                &None,
+                &custom_member_types,
+            )
+            .in_element(Stage::Config, format!("{}.{}", object.name, field.name))?;
+            if resolver_type != ResolverType::Method {
+                continue;
+            }
+            lines.append(&mut vec![
+                "[[resolver]]".to_string(),
+                format!("target_type = {}", toml_string(&object.name)),
+                format!("target_field = {}", toml_string(&field.name)),
+                "resolver_type = \"method\"".to_string(),
+                "# attribute = \"#[graphql(guard = \\\"..\\\")]\"".to_string(),
+                "".to_string(),
+            ]);
+        }
+    }
+
+    let mut enums: Vec<&Enum> = schema.definitions.enums.values().collect();
+    enums.sort_by(sort_by_line_pos_and_name);
+    for enm in enums {
+        lines.append(&mut vec![
+            "[[enum]]".to_string(),
+            format!("target_enum = {}", toml_string(&enm.name.to_camel_case())),
+            "# rename_items = \"UPPERCASE\"".to_string(),
+        ]);
+        if let Some(value) = enm.values.first() {
+            lines.append(&mut vec![
+                "# [[enum.value]]".to_string(),
+                format!(
+                    "# value = {}",
+                    toml_string(&value.value_name.to_camel_case())
+                ),
+                format!("# rename = {}", toml_string(&value.value_name)),
+                "# visible = false".to_string(),
+            ]);
+        }
+        lines.push("".to_string());
+    }
+
+    lines.append(&mut vec![
+        "# [[additional_resolver]]".to_string(),
+        "# target_type = \"Query\"".to_string(),
+        "# body = \"\"\"".to_string(),
+        "#     pub async fn custom_resolver(&self, ctx: &Context<'_>) -> Result<String> { .. }"
+            .to_string(),
+        "# \"\"\"".to_string(),
+        "# using = \"use std::fs\"".to_string(),
+        "".to_string(),
+        "# [[hidden_field]]".to_string(),
+        "# target_type = \"Query\"".to_string(),
+        "# field_def = \"hidden: String\"".to_string(),
+        "# using = \"use std::fs\"".to_string(),
+        "".to_string(),
+        "# Use an existing rust type instead of generating the schema type.".to_string(),
+        "# [[type_mapping]]".to_string(),
+        "# graphql_type = \"DateTime\"".to_string(),
+        "# rust_type = \"DateTime<Utc>\"".to_string(),
+        "# using = \"use chrono::{DateTime, Utc}\"".to_string(),
+        "".to_string(),
+        "# Render a directive of the schema as attributes. {arg} is the value of the argument arg.".to_string(),
+        "# [[directive_mapping]]".to_string(),
+        "# directive = \"rateLimit\"".to_string(),
+        "# attribute = \"#[rate_limit(max = {max})]\"".to_string(),
+        "# datasource_attribute = \"#[tracing::instrument]\"".to_string(),
+        "".to_string(),
+        "# The backing type and checks of a generated scalar. backing_type is one of String, i64, f64,".to_string(),
+        "# bool or serde_json::Value. pattern needs the regex crate and rust 1.70 or later.".to_string(),
+        "# [[scalar]]".to_string(),
+        "# target_scalar = \"Email\"".to_string(),
+        "# backing_type = \"String\"".to_string(),
+        "# pattern = \"^[^@]+@[^@]+$\"".to_string(),
+        "# derives = \"PartialEq, Eq, Hash\"".to_string(),
+        "# specified_by_url = \"https://example.com/email\"".to_string(),
+        "".to_string(),
+        "# [[field]]".to_string(),
+        "# target_type = \"SomeInput\"".to_string(),
+        "# target_field = \"file\"".to_string(),
+        "# replace_field_type = \"Upload!\"".to_string(),
+        "# primitive_type = \"i32\"".to_string(),
+        "# id_of = \"User\"".to_string(),
+        "# argument_id_of = { owner = \"User\" }".to_string(),
+        "# maybe_undefined = true".to_string(),
+        "# visible = false".to_string(),
+        "# secret = true".to_string(),
+        "# secret_arguments = [\"password\"]".to_string(),
+        "".to_string(),
+        "# Hide a type from the schema, or show it only when the function returns true. visible of".to_string(),
+        "# [[field]] and [[enum.value]] takes the same bool or fn(&Context<'_>) -> bool path.".to_string(),
+        "# feature gates the type and everything referring to it with #[cfg(feature = ..)].".to_string(),
+        "# [[type]]".to_string(),
+        "# target_type = \"AdminQuery\"".to_string(),
+        "# visible = \"crate::auth::is_admin\"".to_string(),
+        "# feature = \"admin\"".to_string(),
+        "".to_string(),
+        "# [[additional]]".to_string(),
+        "# body = \"\"\"".to_string(),
+        "# enum SortDirection { ASC DESC }".to_string(),
+        "# \"\"\"".to_string(),
+        "".to_string(),
+        "[ignore]".to_string(),
+        "# object = []".to_string(),
+        "# input_object = []".to_string(),
+        "# enum = []".to_string(),
+        "# union = []".to_string(),
+        "# interface = []".to_string(),
+        "# scalar = []".to_string(),
+    ]);
+
+    Ok(lines.join("\n") + "\n")
+}
+
+fn toml_string(value: &str) -> String {
+    toml::Value::String(value.to_string()).to_string()
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    pub fn config_template_lists_method_fields_and_enums() {
+        let schema = r#"
+        schema {
+          query: Query
+        }
+
+        type Query {
+          me: User!
+        }
+
+        type User {
+          name: String!
+          friends(first: Int): [User!]!
+        }
+
+        enum Status {
+          REGISTERED
+        }
+        "#;
+        let structured_schema = parse_schema(schema, &RendererConfig::default()).unwrap();
+        let template = config_template(&structured_schema).unwrap();
+
+        let config: RendererConfig = toml::from_str(&template).unwrap();
+        let resolvers: Vec<String> = config
+            .resolver
+            .unwrap()
+            .iter()
+            .map(|each| format!("{}.{}", each.target_type, each.target_field))
+            .collect();
+        assert_eq!(resolvers, vec!["Query.me", "User.friends"]);
+        assert_eq!(config.r#enum.unwrap()[0].target_enum, "Status");
+        assert!(config.ignore.unwrap().is_empty());
+    }
+}
@@ -1,12 +1,14 @@
 use super::super::parse::{self, *};
+use super::directives::*;
 use super::fields::*;
 use super::fields::{field_is_method_or_member, ResolverType};
 use super::sorter::sort_by_line_pos_and_name;
+use super::tokens::{cfg_token, datasource_directive_attributes_token};
 use super::typ::*;
 use super::utils::SnakeCaseWithUnderscores;
 use super::RenderContext;
 use crate::config::RendererConfig;
-use anyhow::Result;
+use crate::error::{Result, ResultExt, Stage};
 use proc_macro2::TokenStream;
 use quote::*;
 
@@ -28,10 +30,12 @@ pub fn empty_datasource_methods(
     for object in objects {
         let render_context = RenderContext {
             parent: TypeDef::Object(object),
+            config: render_config,
         };
         let field_resolver = resolver_setting.get(&object.name);
 
         for field in object.fields.iter() {
+            let element = format!("{}.{}", object.name, field.name);
             if let ResolverType::Method = field_is_method_or_member(
                 &field,
                 &schema,
@@ -39,8 +43,13 @@ pub fn empty_datasource_methods(
                 &render_config,
                 &field_resolver,
                 &custom_member_types,
-            )? {
-                result.push(datasouerce_token_method(&field, &schema, &render_context)?);
+            )
+            .in_element(Stage::DataSource, &element)?
+            {
+                result.push(
+                    datasouerce_token_method(field, schema, &render_context)
+                        .in_element(Stage::DataSource, &element)?,
+                );
             }
         }
     }
@@ -48,26 +57,66 @@ pub fn empty_datasource_methods(
     Ok(result)
 }
 
-fn datasouerce_token_method(
+pub(crate) fn datasouerce_token_method(
     field: &parse::Field,
     schema: &StructuredSchema,
     context: &RenderContext,
 ) -> Result<TokenStream> {
-    let parent_name = format_ident!("{}", context.parent_name());
+    let parent_name = context.parent_name()?;
 
-    let resolver_name = format!("{}_{}", context.parent_name(), field.name_string())
-        .to_snake_case_with_underscores();
+    let resolver_name =
+        format!("{}_{}", parent_name, field.name_string()).to_snake_case_with_underscores();
     let resolver_method_name = format_ident!("{}", resolver_name);
+    let parent_name = format_ident!("{}", parent_name);
 
-    let typ = value_type_def_token(&field.typ, &schema, &context)?;
+    let typ = field_type_token(&field.typ, field.primitive_type.as_ref(), schema, context)?;
     let typ: TokenStream = quote! {Result<#typ>};
 
-    let (arg_defs, _) = args_defs_and_values(field, &schema, "_", &context)?;
+    let (arg_defs, _) = args_defs_and_values(field, schema, "_", false, context)?;
+
+    let mut features = match context.parent {
+        TypeDef::Object(object) => object.features.clone(),
+        _ => vec![],
+    };
+    features.extend(field.features.iter().cloned());
+    let cfg = cfg_token(&features);
+    let directive_attributes =
+        datasource_directive_attributes_token(&field.directives, context.config)?;
 
     let q = quote! {
+        #cfg
+        #directive_attributes
         pub async fn #resolver_method_name(&self, _ctx: &Context<'_>, _object: &#parent_name #arg_defs) -> #typ{
             unimplemented!("resolver {} is unimpemented yet", #resolver_name )
         }
     };
     Ok(q)
 }
+
+/// ```ignore
+/// #[async_trait::async_trait]
+/// impl CustomDirective for UpperDirective {
+///     async fn resolve_field(&self, _ctx: &Context<'_>, _resolve: ResolveFut<'_>) -> ServerResult<Option<Value>> {
+///         unimplemented!("directive {} is unimplemented yet", "upper")
+///     }
+/// }
+/// ```
+pub fn empty_custom_directives(schema: &StructuredSchema) -> Vec<TokenStream> {
+    sorted_directives(schema)
+        .into_iter()
+        .map(|directive| {
+            let struct_name = directive_struct_name(directive);
+            let name = &directive.name;
+            let cfg = cfg_token(&directive.features);
+            quote! {
+                #cfg
+                #[async_trait::async_trait]
+                impl CustomDirective for #struct_name {
+                    async fn resolve_field(&self, _ctx: &Context<'_>, _resolve: ResolveFut<'_>) -> ServerResult<Option<Value>> {
+                        unimplemented!("directive {} is unimplemented yet", #name)
+                    }
+                }
+            }
+        })
+        .collect()
+}
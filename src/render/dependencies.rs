@@ -1,7 +1,8 @@
 use super::super::parse::{self, *};
 use super::tokens::*;
 use super::RenderContext;
-use anyhow::Result;
+use crate::config::{Phase, RendererConfig};
+use crate::error::{Error, Result};
 use proc_macro2::TokenStream;
 use quote::*;
 use std::collections::HashSet;
@@ -21,52 +22,90 @@ pub fn dependency(
                 return Ok(vec![]);
             }
             let name = format_ident!("{}", object.name_string());
-            quote! { use super::objects::#name }
+            let path = module_path_token(context, Phase::Objects)?;
+            let cfg = cfg_token(&object.features);
+            quote! { #cfg use #path::#name }
         }
         parse::TypeDef::Enum(enum_kind) => {
             if context.parent.is_enum() {
                 return Ok(vec![]);
             }
             let name = format_ident!("{}", enum_kind.name_string());
-            quote! { use super::enums::#name }
+            let path = module_path_token(context, Phase::Enums)?;
+            let cfg = cfg_token(&enum_kind.features);
+            quote! { #cfg use #path::#name }
         }
         parse::TypeDef::InputObject(input_object) => {
             if context.parent.is_input_object() {
                 return Ok(vec![]);
             }
             let name = format_ident!("{}", input_object.name_string());
-            quote! { use super::input_objects::#name }
+            let path = module_path_token(context, Phase::InputObjects)?;
+            let cfg = cfg_token(&input_object.features);
+            quote! { #cfg use #path::#name }
         }
         parse::TypeDef::Scalar(scalar) => {
             if context.parent.is_scalar() {
                 return Ok(vec![]);
             }
             let name = format_ident!("{}", scalar.name_string());
-            quote! { use super::scalars::#name }
+            let path = module_path_token(context, Phase::Scalars)?;
+            let cfg = cfg_token(&scalar.features);
+            quote! { #cfg use #path::#name }
         }
         parse::TypeDef::Union(union) => {
             if context.parent.is_union() {
                 return Ok(vec![]);
             }
             let name = format_ident!("{}", union.name_string());
-            quote! { use super::unions::#name }
+            let path = module_path_token(context, Phase::Unions)?;
+            let cfg = cfg_token(&union.features);
+            quote! { #cfg use #path::#name }
         }
         parse::TypeDef::Interface(interface) => {
             if context.parent.is_interface() {
                 return Ok(vec![]);
             }
             let name = format_ident!("{}", interface.name_string());
-            quote! { use super::interfaces::#name }
+            let path = module_path_token(context, Phase::Interfaces)?;
+            let cfg = cfg_token(&interface.features);
+            quote! { #cfg use #path::#name }
         }
 
         parse::TypeDef::AsyncGraphqlPreserved(_) => {
             // imported by use ayncgraphql::*;
             return Ok(vec![]);
         }
+        parse::TypeDef::Mapped(mapped_type) => match &mapped_type.using {
+            Some(using) => config_snippet_token(using, &mapped_type.entry_name)?,
+            None => return Ok(vec![]),
+        },
+        parse::TypeDef::Directive(directive) => {
+            return Err(Error::UndefinedType(directive.name_string()));
+        }
     };
     Ok(vec![result])
 }
 
+fn module_path_token(context: &RenderContext, phase: Phase) -> Result<TokenStream> {
+    config_snippet_token(
+        context.config.module_path.of(&phase),
+        &format!("[module_path] {}", phase.module_name()),
+    )
+}
+
+/// The `[using]` declarations of the module, tokenized the same as the ones from `dependency`
+/// so an import both of them add is written once.
+pub fn config_dependencies(config: &RendererConfig, module: &str) -> Result<HashSet<String>> {
+    config
+        .using(module)
+        .iter()
+        .map(|(entry, declaration)| {
+            config_snippet_token(declaration, entry).map(|token| token.to_string())
+        })
+        .collect()
+}
+
 pub fn dependency_strs_to_token(dependencies: HashSet<String>) -> TokenStream {
     merge_with_trailing_semicomman(
         dependencies
@@ -78,3 +117,84 @@ pub fn dependency_strs_to_token(dependencies: HashSet<String>) -> TokenStream {
             .collect(),
     )
 }
+
+#[cfg(test)]
+mod test {
+
+    use super::super::{render_for_test, syntax, ParsedSource};
+
+    #[test]
+    pub fn add_using_declarations_to_the_modules() {
+        let schema = r#"
+        type Query {
+          active: Boolean!
+        }
+
+        enum Status {
+          REGISTERED
+        }
+        "#;
+        let (files, datasource_files) = render_for_test(
+            schema,
+            r#"
+            [using]
+            all = "use std::fmt"
+            enums = "use std::str::FromStr; use std::fmt"
+            data_source = "use super::output::*"
+            "#,
+        )
+        .unwrap();
+
+        // the use declarations of the config, the generated ones are left out.
+        let std_uses = |source: &ParsedSource| -> Vec<syn::ItemUse> {
+            source
+                .uses()
+                .into_iter()
+                .filter(
+                    |item| matches!(&item.tree, syn::UseTree::Path(path) if path.ident == "std"),
+                )
+                .cloned()
+                .collect()
+        };
+        let enums = ParsedSource::of(&files, "enums.rs");
+        assert_eq!(
+            std_uses(&enums),
+            vec![syntax("use std::fmt;"), syntax("use std::str::FromStr;")]
+        );
+        let objects = ParsedSource::of(&files, "objects.rs");
+        assert_eq!(std_uses(&objects), vec![syntax("use std::fmt;")]);
+        let datasource = ParsedSource::of(&datasource_files, "mod.rs");
+        assert!(datasource.has_use("use super::output::*;"));
+        assert!(datasource.has_use("use std::fmt;"));
+    }
+
+    #[test]
+    pub fn import_types_from_module_paths() {
+        let schema = r#"
+        type Query {
+          status: Status!
+        }
+
+        enum Status {
+          REGISTERED
+        }
+        "#;
+        let (files, _) = render_for_test(
+            schema,
+            r#"
+            phases = ["objects"]
+
+            [module_path]
+            enums = "api_types::enums"
+            data_source = "crate::api::DataSource"
+            "#,
+        )
+        .unwrap();
+
+        let objects = ParsedSource::of(&files, "objects.rs");
+        assert!(objects.has_use("use api_types::enums::Status;"));
+        assert!(objects.has_use("use crate::api::DataSource;"));
+        assert!(!objects.has_use("use super::enums::Status;"));
+        assert!(!files.contains("enums.rs"));
+    }
+}
@@ -0,0 +1,207 @@
+use super::super::parse::*;
+use super::argument::argument_def_token;
+use super::comment::*;
+use super::dependencies::*;
+use super::keywords::RUST_KEYWORDS;
+use super::sorter::sort_by_line_pos_and_name;
+use super::tokens::*;
+use super::utils::SnakeCaseWithUnderscores;
+use super::RenderContext;
+use crate::config::{Phase, RendererConfig};
+use crate::error::{Result, ResultExt, Stage};
+use heck::CamelCase;
+use proc_macro2::{Ident, TokenStream};
+use quote::*;
+
+pub fn directives_source(
+    structured_schema: &StructuredSchema,
+    config: &RendererConfig,
+) -> Result<Option<String>> {
+    let directives = sorted_directives(structured_schema);
+    if directives.is_empty() {
+        return Ok(None);
+    }
+
+    let mut all_dependencies = config_dependencies(config, Phase::Directives.module_name())?;
+    let mut directive_defs = Vec::<String>::new();
+
+    for each_directive in directives {
+        let (directive_token, dependencies) =
+            directive_token(each_directive, structured_schema, config)
+                .in_element(Stage::Directives, &each_directive.name)?;
+
+        directive_defs.push(directive_token.to_string());
+
+        for each_dep in dependencies.into_iter() {
+            all_dependencies.insert(each_dep.to_string());
+        }
+    }
+
+    let mut source = String::new();
+    source.push_str(FILE_HEADER_COMMENT);
+    let header = quote! {
+        use async_graphql::*;
+    };
+
+    source.push_str(&header.to_string());
+    let dependencies_token = dependency_strs_to_token(all_dependencies);
+
+    source.push_str(&dependencies_token.to_string());
+    for each_directive_def in directive_defs {
+        source.push_str(&each_directive_def);
+    }
+
+    Ok(Some(source))
+}
+
+pub fn sorted_directives(structured_schema: &StructuredSchema) -> Vec<&DirectiveDefinition> {
+    let mut directives: Vec<&DirectiveDefinition> =
+        structured_schema.definitions.directives.values().collect();
+    directives.sort_by(sort_by_line_pos_and_name);
+    directives
+}
+
+/// The struct holding the arguments of the directive, which the `CustomDirective` is implemented for.
+pub fn directive_struct_name(directive: &DirectiveDefinition) -> Ident {
+    format_ident!("{}Directive", directive.name.to_camel_case())
+}
+
+/// The `#[Directive]` factory function registered to the schema.
+pub fn directive_factory_name(directive: &DirectiveDefinition) -> Ident {
+    let name = directive.name.to_snake_case_with_underscores();
+    if RUST_KEYWORDS.contains(&name.as_ref()) {
+        format_ident!("r#{}", name)
+    } else {
+        format_ident!("{}", name)
+    }
+}
+
+/// ```ignore
+/// pub struct UpperDirective {
+///     pub prefix: Option<String>,
+/// }
+///
+/// #[Directive(name = "upper", location = "Field")]
+/// pub fn upper(prefix: Option<String>) -> impl CustomDirective {
+///     UpperDirective { prefix }
+/// }
+/// ```
+fn directive_token(
+    directive: &DirectiveDefinition,
+    schema: &StructuredSchema,
+    config: &RendererConfig,
+) -> Result<(TokenStream, Vec<TokenStream>)> {
+    let context = RenderContext {
+        parent: TypeDef::Directive(directive),
+        config,
+    };
+    let comment = match &directive.description {
+        Some(desc_token) => to_rust_docs_token(desc_token),
+        None => quote! {},
+    };
+
+    let mut dependencies = Vec::<TokenStream>::new();
+    let mut arg_defs = Vec::<TokenStream>::new();
+    let mut arg_values = Vec::<TokenStream>::new();
+    for argument in directive.arguments.iter() {
+        arg_defs.push(argument_def_token(argument, schema, "", false, &context)?);
+        let arg = format_ident!(
+            "{}",
+            argument.name_string().to_snake_case_with_underscores()
+        );
+        arg_values.push(quote! {#arg});
+        dependencies.extend(dependency(&argument.typ, schema, &context)?);
+    }
+    let members = separate_by_comma(arg_defs.iter().map(|arg| quote! { pub #arg }).collect());
+    let arg_defs = separate_by_comma(arg_defs);
+    let arg_values = separate_by_comma(arg_values);
+
+    let struct_name = directive_struct_name(directive);
+    let factory_name = directive_factory_name(directive);
+    let name = &directive.name;
+    let cfg = cfg_token(&directive.features);
+    let directive_def = quote! {
+
+        #cfg
+        pub struct #struct_name {
+            #members
+        }
+
+        #comment
+        #cfg
+        #[Directive(name = #name, location = "Field")]
+        pub fn #factory_name(#arg_defs) -> impl CustomDirective {
+            #struct_name { #arg_values }
+        }
+
+    };
+    Ok((directive_def, dependencies))
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::super::{attribute, render_for_test, syntax, ParsedSource};
+
+    #[test]
+    pub fn render_custom_directives() {
+        let schema = r#"
+        schema {
+          query: Query
+        }
+
+        directive @upper(prefix: String, mode: Mode) on FIELD | FRAGMENT_SPREAD
+        directive @trace on QUERY
+        directive @key(fields: String!) on OBJECT
+
+        type Query {
+          name: String!
+        }
+
+        enum Mode {
+          LOUD
+        }
+        "#;
+        let (files, datasource_files) = render_for_test(schema, "").unwrap();
+
+        // the directive is served by the directives test of schema_macro.
+        let directives = ParsedSource::of(&files, "directives.rs");
+        assert!(directives.has_use("use super::enums::Mode;"));
+        assert_eq!(directives.struct_names(), vec!["UpperDirective"]);
+        assert_eq!(
+            *directives.structure("UpperDirective"),
+            syntax(
+                "pub struct UpperDirective { pub prefix: Option<String>, pub mode: Option<Mode>, }"
+            )
+        );
+        assert_eq!(directives.function_names(), vec!["upper"]);
+        assert_eq!(
+            *directives.function("upper"),
+            syntax(
+                r#"#[Directive(name = "upper", location = "Field")]
+                pub fn upper(prefix: Option<String>, mode: Option<Mode>) -> impl CustomDirective {
+                    UpperDirective { prefix, mode }
+                }"#
+            )
+        );
+        let mod_source = ParsedSource::of(&files, "mod.rs");
+        assert_eq!(
+            *mod_source.function("schema_builder").block,
+            syntax(
+                "{
+                    let builder = Schema::build(Query {}, EmptyMutation {}, EmptySubscription);
+                    let builder = builder.directive(upper);
+                    builder
+                }"
+            )
+        );
+
+        let datasource = ParsedSource::of(&datasource_files, "mod.rs");
+        let stub = datasource.impls("UpperDirective")[0];
+        assert_eq!(stub.attrs, vec![attribute("#[async_trait::async_trait]")]);
+        assert_eq!(
+            stub.trait_.as_ref().map(|(_, path, _)| path),
+            Some(&syntax("CustomDirective"))
+        );
+    }
+}
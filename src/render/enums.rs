@@ -1,31 +1,19 @@
 use super::super::parse::*;
 use super::comment::*;
-use super::files::{fmt_file, pathbuf_to_str};
+use super::dependencies::*;
 use super::sorter::sort_by_line_pos_and_name;
 use super::tokens::*;
-use crate::config::{EnumSetting, EnumValueSetting, RendererConfig};
-use anyhow::Result;
+use crate::config::{EnumSetting, EnumValueSetting, Phase, RendererConfig};
+use crate::error::{Result, ResultExt, Stage};
 use heck::CamelCase;
 use proc_macro2::TokenStream;
 use quote::*;
 use std::collections::HashMap;
-use std::fs::{self, OpenOptions};
-use std::io::{BufWriter, Write};
-use std::path::PathBuf;
 
-pub fn write_enums(
-    output_dir: &str,
+pub fn enums_source(
     structured_schema: &StructuredSchema,
     config: &RendererConfig,
-) -> Result<bool> {
-    let mut output_file = PathBuf::from(output_dir);
-    output_file.push("enums.rs");
-    let file_path_str = pathbuf_to_str(&output_file);
-
-    if output_file.exists() {
-        fs::remove_file(&file_path_str)?;
-    }
-
+) -> Result<Option<String>> {
     let mut enums: Vec<&Enum> = structured_schema
         .definitions
         .enums
@@ -33,7 +21,7 @@ pub fn write_enums(
         .into_iter()
         .collect();
     if enums.is_empty() {
-        return Ok(false);
+        return Ok(None);
     }
     enums.sort_by(sort_by_line_pos_and_name);
 
@@ -42,31 +30,27 @@ pub fn write_enums(
     let enum_settings = config.enum_settings();
 
     for each_enum in enums {
-        let enum_token = enum_token(each_enum, &structured_schema, config, &enum_settings)?;
+        let enum_token = enum_token(each_enum, structured_schema, config, &enum_settings)
+            .in_element(Stage::Enums, &each_enum.name)?;
         enum_defs.push(enum_token.to_string());
     }
 
-    let dest_file = OpenOptions::new()
-        .write(true)
-        .create(true)
-        .open(output_file.as_path())
-        .expect(format!("failed to open file : {}", file_path_str).as_ref());
-    let mut dest_file = BufWriter::new(dest_file);
-
-    dest_file.write(FILE_HEADER_COMMENT.as_bytes())?;
+    let mut source = String::new();
+    source.push_str(FILE_HEADER_COMMENT);
     let header = quote! {
         use async_graphql::*;
     };
 
-    dest_file.write(header.to_string().as_bytes())?;
+    source.push_str(&header.to_string());
+    let dependencies_token =
+        dependency_strs_to_token(config_dependencies(config, Phase::Enums.module_name())?);
+    source.push_str(&dependencies_token.to_string());
 
     for each_obj_def in enum_defs {
-        dest_file.write(each_obj_def.as_bytes())?;
+        source.push_str(&each_obj_def);
     }
 
-    dest_file.flush()?;
-    fmt_file(file_path_str)?;
-    Ok(true)
+    Ok(Some(source))
 }
 
 fn enum_token(
@@ -105,24 +89,27 @@ fn enum_token(
             let enum_value_name = each_enum_value.value_name.to_camel_case();
             let each_enum = format_ident!("{}", enum_value_name);
 
-            let enum_attribute = match enum_value_settings.get(&enum_value_name) {
+            let mut attribute_arguments = match enum_value_settings.get(&enum_value_name) {
                 Some(each_enum_setting) => match &each_enum_setting.rename {
-                    Some(rename) => {
-                        quote! {
-                            #[graphql(name = #rename)]
-                        }
-                    }
-                    None => quote! {},
+                    Some(rename) => vec![quote! { name = #rename }],
+                    None => vec![],
                 },
-                None => quote! {},
+                None => vec![],
             };
+            if let Some(visible) = &each_enum_value.visible {
+                attribute_arguments.push(visible_token(visible));
+            }
+            let enum_attribute = graphql_attribute_token(attribute_arguments);
+            let directive_attributes =
+                directive_attributes_token(&each_enum_value.directives, config)?;
 
-            quote! {
+            Ok(quote! {
                 #enum_attribute
+                #directive_attributes
                 #each_enum
-            }
+            })
         })
-        .collect();
+        .collect::<Result<Vec<TokenStream>>>()?;
 
     if !there_is_specific_rename_item {
         if let Some(enum_rename_items) = config.enum_rename_items.as_ref() {
@@ -134,11 +121,18 @@ fn enum_token(
 
     let enum_name = format_ident!("{}", enum_name);
     let enum_members = separate_by_comma(enums_members);
+    let visible_attribute =
+        graphql_attribute_token(enm.visible.iter().map(visible_token).collect());
 
+    let cfg = cfg_token(&enm.features);
+    let directive_attributes = directive_attributes_token(&enm.directives, config)?;
     let enum_def = quote! {
 
+        #cfg
         #[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
         #graphql_derive
+        #visible_attribute
+        #directive_attributes
         pub enum #enum_name{
             #enum_members
         }
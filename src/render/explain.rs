@@ -0,0 +1,709 @@
+//! `explain Type.field`: how a field is rendered and the config entries that touch it.
+
+use super::super::parse::*;
+use super::datasource::datasouerce_token_method;
+use super::fields::{convert_field, resolver_type_and_reason, MemberAndMethod, ResolverType};
+use super::files::fmt_source;
+use super::input_fields::{convert_input_field, InputMember};
+use super::typ::{field_type_token, input_value_type_token};
+use super::utils::SnakeCaseWithUnderscores;
+use super::RenderContext;
+use crate::config::*;
+use crate::error::{Error, Result};
+use proc_macro2::TokenStream;
+use quote::*;
+use std::str::FromStr;
+
+/// A report of how `target`, e.g. `User.friends`, is generated. `parsed` is the schema before
+/// `[ignore]` and the tags are applied and `filtered` the one after, as `parse_and_filter_schema_files`
+/// returns them.
+pub fn explain_field(
+    parsed: &StructuredSchema,
+    filtered: &StructuredSchema,
+    config: &RendererConfig,
+    target: &str,
+) -> Result<String> {
+    let config = &*config.with_inline_settings(&parsed.inline_settings);
+    let (type_name, field_name) = target
+        .split_once('.')
+        .ok_or_else(|| Error::UndefinedField(target.to_string()))?;
+    let definitions = &parsed.definitions;
+    if !definitions.objects.contains_key(type_name)
+        && !definitions.interfaces.contains_key(type_name)
+        && !definitions.input_objects.contains_key(type_name)
+    {
+        return Err(Error::UndefinedType(type_name.to_string()));
+    }
+
+    let mut lines = Vec::<String>::new();
+    let field_type = definitions
+        .objects
+        .get(type_name)
+        .map(|object| &object.fields)
+        .or_else(|| {
+            definitions
+                .interfaces
+                .get(type_name)
+                .map(|intf| &intf.fields)
+        })
+        .and_then(|fields| fields.iter().find(|field| field.name == field_name))
+        .map(|field| &field.typ)
+        .or_else(|| {
+            definitions
+                .input_objects
+                .get(type_name)
+                .and_then(|input| input.fields.iter().find(|field| field.name == field_name))
+                .map(|field| &field.typ)
+        });
+    match field_type {
+        Some(typ) => lines.push(format!("{}: {}", target, graphql_type(typ))),
+        None => {
+            let hidden_field = config.hidden_field.iter().flatten().find(|hidden_field| {
+                hidden_field.target_type == type_name
+                    && hidden_field.field_def.split(':').next().map(str::trim)
+                        == Some(&field_name.to_snake_case_with_underscores())
+            });
+            return match hidden_field {
+                Some(hidden_field) => Ok(format!(
+                    "{} is not in the schema\nhidden member added by {}: {}\n",
+                    target,
+                    hidden_field.entry_name(),
+                    hidden_field.field_def
+                )),
+                None => Err(Error::UndefinedField(target.to_string())),
+            };
+        }
+    }
+
+    if let Some(reason) = removal_reason(type_name, parsed, filtered, config) {
+        lines.push(format!("removed: {}", reason));
+    } else if let Some(object) = filtered.definitions.objects.get(type_name) {
+        match object.fields.iter().find(|field| field.name == field_name) {
+            Some(field) => explain_object_field(object, field, filtered, config, &mut lines)?,
+            None => lines.push(format!(
+                "removed: {}",
+                field_removal_reason(
+                    referred_type_names(&definitions.objects[type_name].fields, field_name),
+                    parsed,
+                    filtered,
+                    config
+                )
+            )),
+        }
+    } else if let Some(interface) = filtered.definitions.interfaces.get(type_name) {
+        match interface
+            .fields
+            .iter()
+            .find(|field| field.name == field_name)
+        {
+            Some(field) => {
+                let context = RenderContext {
+                    parent: TypeDef::Interface(interface),
+                    config,
+                };
+                let typ = field_type_token(
+                    &field.typ,
+                    field.primitive_type.as_ref(),
+                    filtered,
+                    &context,
+                )?;
+                lines.push(format!("rust type: {}", rust_type(typ)?));
+                lines.push(
+                    "rendered as: field of the interface, resolved by the implementing objects"
+                        .to_string(),
+                );
+            }
+            None => lines.push(format!(
+                "removed: {}",
+                field_removal_reason(
+                    referred_type_names(&definitions.interfaces[type_name].fields, field_name),
+                    parsed,
+                    filtered,
+                    config
+                )
+            )),
+        }
+    } else if let Some(input_object) = filtered.definitions.input_objects.get(type_name) {
+        match input_object
+            .fields
+            .iter()
+            .find(|field| field.name == field_name)
+        {
+            Some(field) => {
+                let context = RenderContext {
+                    parent: TypeDef::InputObject(input_object),
+                    config,
+                };
+                let typ = input_value_type_token(
+                    &field.typ,
+                    field.primitive_type.as_ref(),
+                    field.maybe_undefined,
+                    filtered,
+                    &context,
+                )?;
+                lines.push(format!("rust type: {}", rust_type(typ)?));
+                lines.push("rendered as: member of the input object".to_string());
+                let InputMember { member, .. } = convert_input_field(field, filtered, &context)?;
+                let name = format_ident!("{}", input_object.name);
+                push_code(&mut lines, quote! { pub struct #name { #member } })?;
+            }
+            None => lines.push(format!(
+                "removed: {}",
+                field_removal_reason(
+                    field_type
+                        .map(ValueTypeDef::element_type_name)
+                        .into_iter()
+                        .collect(),
+                    parsed,
+                    filtered,
+                    config
+                )
+            )),
+        }
+    }
+
+    let entries = config_entries(type_name, field_name, parsed, config);
+    if entries.is_empty() {
+        lines.push("config: none".to_string());
+    } else {
+        lines.push("config:".to_string());
+        lines.extend(entries.into_iter().map(|entry| format!("  {}", entry)));
+    }
+
+    let mut report = lines.join("\n");
+    report.push('\n');
+    Ok(report)
+}
+
+fn explain_object_field(
+    object: &Object,
+    field: &Field,
+    schema: &StructuredSchema,
+    config: &RendererConfig,
+    lines: &mut Vec<String>,
+) -> Result<()> {
+    let context = RenderContext {
+        parent: TypeDef::Object(object),
+        config,
+    };
+    let resolver_settings = config.resolver_setting();
+    let resolver_settings = resolver_settings.get(&object.name);
+    let custom_member_types = config.custom_member_types();
+
+    let typ = field_type_token(&field.typ, field.primitive_type.as_ref(), schema, &context)?;
+    lines.push(format!("rust type: {}", rust_type(typ)?));
+
+    let (resolver_type, reason) = resolver_type_and_reason(
+        field,
+        schema,
+        &context,
+        config,
+        &resolver_settings,
+        &custom_member_types,
+    )?;
+    let rendered_as = match resolver_type {
+        ResolverType::Field => "member",
+        ResolverType::Method => "method",
+    };
+    lines.push(format!(
+        "rendered as: {}, decided by {}",
+        rendered_as, reason
+    ));
+
+    let MemberAndMethod { member, method, .. } = convert_field(
+        field,
+        schema,
+        &context,
+        config,
+        &resolver_settings,
+        &custom_member_types,
+    )?;
+    let name = format_ident!("{}", object.name);
+    if let Some(member) = member {
+        push_code(lines, quote! { pub struct #name { #member } })?;
+    }
+    if let Some(method) = method {
+        push_code(lines, quote! { impl #name { #method } })?;
+    }
+
+    if resolver_type == ResolverType::Method {
+        lines.push(format!(
+            "datasource method: {}",
+            format!("{}_{}", object.name, field.name_string()).to_snake_case_with_underscores()
+        ));
+        let stub = datasouerce_token_method(field, schema, &context)?;
+        push_code(lines, quote! { impl DataSource { #stub } })?;
+    }
+    Ok(())
+}
+
+/// `[User!]!`
+fn graphql_type(typ: &ValueTypeDef) -> String {
+    let (name, is_nullable) = match typ {
+        ValueTypeDef::Named(named) => (named.value_type_name.to_string(), named.is_nullable),
+        ValueTypeDef::List(list) => (format!("[{}]", graphql_type(&list.inner)), list.is_nullable),
+    };
+    if is_nullable {
+        name
+    } else {
+        format!("{}!", name)
+    }
+}
+
+fn rust_type(typ: TokenStream) -> Result<String> {
+    let source = fmt_source("explain", &quote! { type T = #typ; }.to_string())?;
+    Ok(source
+        .trim()
+        .trim_start_matches("type T = ")
+        .trim_end_matches(';')
+        .to_string())
+}
+
+fn push_code(lines: &mut Vec<String>, code: TokenStream) -> Result<()> {
+    let source = fmt_source("explain", &code.to_string())?;
+    lines.extend(source.lines().map(|line| format!("    {}", line)));
+    Ok(())
+}
+
+fn is_defined(definitions: &Definitions, type_name: &str) -> bool {
+    definitions.objects.contains_key(type_name)
+        || definitions.interfaces.contains_key(type_name)
+        || definitions.input_objects.contains_key(type_name)
+        || definitions.enums.contains_key(type_name)
+        || definitions.unions.contains_key(type_name)
+        || definitions.scalars.contains_key(type_name)
+        || definitions.mapped_types.contains_key(type_name)
+}
+
+/// Why the type defined in `parsed` is not in `filtered`.
+fn removal_reason(
+    type_name: &str,
+    parsed: &StructuredSchema,
+    filtered: &StructuredSchema,
+    config: &RendererConfig,
+) -> Option<String> {
+    if !is_defined(&parsed.definitions, type_name) || is_defined(&filtered.definitions, type_name) {
+        return None;
+    }
+    let ignored = config.ignore.as_ref().and_then(|ignore| {
+        [
+            ("object", &ignore.object),
+            ("enum", &ignore.r#enum),
+            ("input_object", &ignore.input_object),
+            ("union", &ignore.union),
+            ("interface", &ignore.interface),
+            ("scalar", &ignore.scalar),
+        ]
+        .iter()
+        .find(|(_, names)| names.iter().flatten().any(|name| name == type_name))
+        .map(|(key, _)| format!("[ignore] {} = {:?}", key, type_name))
+    });
+    Some(
+        ignored
+            .unwrap_or_else(|| format!("{} is removed by include_tags or exclude_tags", type_name)),
+    )
+}
+
+/// Why the field of a type kept in `filtered` is not there, given the types the field refers to.
+fn field_removal_reason(
+    type_names: Vec<&str>,
+    parsed: &StructuredSchema,
+    filtered: &StructuredSchema,
+    config: &RendererConfig,
+) -> String {
+    type_names
+        .into_iter()
+        .find_map(|type_name| {
+            removal_reason(type_name, parsed, filtered, config)
+                .map(|reason| format!("type {} is removed, {}", type_name, reason))
+        })
+        .unwrap_or_else(|| "by include_tags or exclude_tags".to_string())
+}
+
+/// The type of the field and of its arguments.
+fn referred_type_names<'a>(fields: &'a [Field], field_name: &str) -> Vec<&'a str> {
+    match fields.iter().find(|field| field.name == field_name) {
+        Some(field) => std::iter::once(field.typ.element_type_name())
+            .chain(
+                field
+                    .arguments
+                    .iter()
+                    .map(|argument| argument.typ.element_type_name()),
+            )
+            .collect(),
+        None => vec![],
+    }
+}
+
+/// The entries of the config that apply to the field, with the keys they set.
+fn config_entries(
+    type_name: &str,
+    field_name: &str,
+    parsed: &StructuredSchema,
+    config: &RendererConfig,
+) -> Vec<String> {
+    let mut entries = Vec::<String>::new();
+    for field in config.field.iter().flatten() {
+        if field.target_type != type_name || field.target_field != field_name {
+            continue;
+        }
+        let mut keys = Vec::<String>::new();
+        if let Some(replace_field_type) = &field.replace_field_type {
+            keys.push(format!("replace_field_type = {:?}", replace_field_type));
+        }
+        if let Some(primitive_type) = &field.primitive_type {
+            keys.push(format!("primitive_type = {:?}", primitive_type));
+        }
+        if let Some(id_of) = &field.id_of {
+            keys.push(format!("id_of = {:?}", id_of));
+        }
+        if field.argument_id_of.is_some() {
+            keys.push("argument_id_of".to_string());
+        }
+        if let Some(maybe_undefined) = field.maybe_undefined {
+            keys.push(format!("maybe_undefined = {}", maybe_undefined));
+        }
+        if field.visible.is_some() {
+            keys.push("visible".to_string());
+        }
+        if let Some(secret) = field.secret {
+            keys.push(format!("secret = {}", secret));
+        }
+        if field.secret_arguments.is_some() {
+            keys.push("secret_arguments".to_string());
+        }
+        entries.push(format!("{}: {}", field.entry_name(), keys.join(", ")));
+    }
+
+    for resolver in config.resolver.iter().flatten() {
+        if resolver.target_type != type_name || resolver.target_field != field_name {
+            continue;
+        }
+        let mut keys = Vec::<String>::new();
+        if let Some(resolver_type) = &resolver.resolver_type {
+            keys.push(format!("resolver_type = {:?}", resolver_type));
+        }
+        if let Some(attribute) = &resolver.attribute {
+            keys.push(format!("attribute = {:?}", attribute));
+        }
+        for argument in resolver.argument.iter().flatten() {
+            keys.push(format!(
+                "argument {}: {}",
+                argument.arg_name, argument.arg_type
+            ));
+        }
+        entries.push(format!("{}: {}", resolver.entry_name(), keys.join(", ")));
+    }
+
+    let definitions = &parsed.definitions;
+    let field = definitions
+        .objects
+        .get(type_name)
+        .map(|object| &object.fields)
+        .or_else(|| {
+            definitions
+                .interfaces
+                .get(type_name)
+                .map(|intf| &intf.fields)
+        })
+        .and_then(|fields| fields.iter().find(|field| field.name == field_name));
+    let input_field = definitions
+        .input_objects
+        .get(type_name)
+        .and_then(|input| input.fields.iter().find(|field| field.name == field_name));
+
+    // the types the field refers to, the directives applied to it and whether it has input values.
+    let (types, directives, primitive_type, has_input_values) = match (field, input_field) {
+        (Some(field), _) => (
+            std::iter::once(&field.typ)
+                .chain(field.arguments.iter().map(|argument| &argument.typ))
+                .collect::<Vec<&ValueTypeDef>>(),
+            field
+                .directives
+                .iter()
+                .chain(
+                    field
+                        .arguments
+                        .iter()
+                        .flat_map(|argument| argument.directives.iter()),
+                )
+                .collect::<Vec<&Directive>>(),
+            field.primitive_type.as_ref(),
+            !field.arguments.is_empty(),
+        ),
+        (None, Some(input_field)) => (
+            vec![&input_field.typ],
+            input_field.directives.iter().collect(),
+            input_field.primitive_type.as_ref(),
+            true,
+        ),
+        (None, None) => return entries,
+    };
+
+    if let (Some(field), Some(_)) = (field, definitions.objects.get(type_name)) {
+        for rule in config.resolver_rule.iter().flatten() {
+            if !rule.matches(type_name, field, definitions).unwrap_or(false) {
+                continue;
+            }
+            let mut keys = Vec::<String>::new();
+            if let Some(resolver_type) = &rule.resolver_type {
+                keys.push(format!("resolver_type = {:?}", resolver_type));
+            }
+            if let Some(attribute) = &rule.attribute {
+                keys.push(format!("attribute = {:?}", attribute));
+            }
+            entries.push(format!("{}: {}", rule.entry_name(), keys.join(", ")));
+        }
+        let element_type_name = field.typ.element_type_name();
+        if config
+            .custom_member_types
+            .iter()
+            .flatten()
+            .any(|name| name == element_type_name)
+        {
+            entries.push(format!(
+                "custom_member_types: contains {}",
+                element_type_name
+            ));
+        }
+    }
+
+    let mut type_names = Vec::<&str>::new();
+    for typ in types {
+        if !type_names.contains(&typ.element_type_name()) {
+            type_names.push(typ.element_type_name());
+        }
+    }
+    for mapped_type in type_names
+        .iter()
+        .filter_map(|name| definitions.mapped_types.get(*name))
+    {
+        entries.push(format!(
+            "{}: rust_type = {:?}",
+            mapped_type.entry_name, mapped_type.rust_type
+        ));
+    }
+
+    if primitive_type.is_none() {
+        for kind in type_names
+            .iter()
+            .filter_map(|name| PrimitiveKind::from_str(name).ok())
+        {
+            if let Some(rust_type) = config.primitive.get(&kind) {
+                entries.push(format!(
+                    "{} = {:?}",
+                    PrimitiveSetting::entry_name(&kind),
+                    rust_type
+                ));
+            }
+        }
+    }
+
+    if config.typed_ids {
+        for name in type_names.iter() {
+            let typed_id = definitions
+                .scalars
+                .get(*name)
+                .and_then(|scalar| scalar.id_of.as_ref());
+            if let Some(owner) = typed_id {
+                entries.push(format!("typed_ids: {} is the id of {}", name, owner));
+            }
+        }
+    }
+
+    let field_maybe_undefined = config.field.iter().flatten().any(|field| {
+        field.target_type == type_name
+            && field.target_field == field_name
+            && field.maybe_undefined.is_some()
+    });
+    if has_input_values && !field_maybe_undefined {
+        if config.maybe_undefined {
+            entries.push("maybe_undefined = true".to_string());
+        }
+        if config
+            .maybe_undefined_types
+            .iter()
+            .any(|name| name == type_name)
+        {
+            entries.push(format!("maybe_undefined_types: contains {}", type_name));
+        }
+    }
+
+    for type_setting in config.r#type.iter().flatten() {
+        let mut keys = Vec::<String>::new();
+        if type_setting.target_type == type_name {
+            if type_setting.visible.is_some() {
+                keys.push("visible".to_string());
+            }
+            if let Some(feature) = &type_setting.feature {
+                keys.push(format!("feature = {:?}", feature));
+            }
+        } else if type_names.contains(&type_setting.target_type.as_str()) {
+            if let Some(feature) = &type_setting.feature {
+                keys.push(format!("feature = {:?}", feature));
+            }
+        }
+        if !keys.is_empty() {
+            entries.push(format!(
+                "{}: {}",
+                type_setting.entry_name(),
+                keys.join(", ")
+            ));
+        }
+    }
+
+    for directive in directives {
+        for mapping in config
+            .directive_mapping
+            .iter()
+            .flatten()
+            .filter(|mapping| mapping.directive == directive.name)
+        {
+            let mut keys = Vec::<String>::new();
+            if let Some(attribute) = &mapping.attribute {
+                keys.push(format!("attribute = {:?}", attribute));
+            }
+            if let Some(datasource_attribute) = &mapping.datasource_attribute {
+                keys.push(format!("datasource_attribute = {:?}", datasource_attribute));
+            }
+            entries.push(format!("{}: {}", mapping.entry_name(), keys.join(", ")));
+        }
+    }
+    entries
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn explain_resolver_type_and_entries() {
+        let schema = r#"
+        schema {
+          query: Query
+        }
+
+        type Query {
+          user(id: ID!): User
+        }
+
+        type User {
+          name: String!
+          friends(first: Int): [User!]!
+          posts: [Post!]!
+        }
+
+        type Post {
+          title: String!
+        }
+        "#;
+        let config: RendererConfig = toml::from_str(
+            r##"
+            [[resolver]]
+            target_type = "User"
+            target_field = "friends"
+            attribute = "#[allow(unused)]"
+
+            [[field]]
+            target_type = "User"
+            target_field = "friends"
+            replace_field_type = "[User!]"
+
+            [ignore]
+            object = ["Post"]
+            "##,
+        )
+        .unwrap();
+        let parsed = parse_schema(schema, &config).unwrap();
+        let mut filtered = parsed.clone();
+        crate::parse::ignoring::remove_ignored_from_structure(&mut filtered, &config).unwrap();
+
+        let report = explain_field(&parsed, &filtered, &config, "User.friends").unwrap();
+        assert!(report.starts_with("User.friends: [User!]\nrust type: Option<Vec<User>>\n"));
+        assert!(report.contains("rendered as: method, decided by heuristic"));
+        assert!(report.contains("datasource method: user_friends\n"));
+        assert!(report.contains(
+            r#"[[field]] target_type = "User", target_field = "friends": replace_field_type = "[User!]""#
+        ));
+        assert!(report.contains(
+            r##"[[resolver]] target_type = "User", target_field = "friends": attribute = "#[allow(unused)]""##
+        ));
+
+        let report = explain_field(&parsed, &filtered, &config, "User.name").unwrap();
+        assert!(report.contains("rendered as: member, decided by heuristic"));
+        assert!(report.ends_with("config: none\n"));
+
+        let report = explain_field(&parsed, &filtered, &config, "User.posts").unwrap();
+        assert!(report.contains(r#"removed: type Post is removed, [ignore] object = "Post""#));
+
+        assert!(explain_field(&parsed, &filtered, &config, "User.nmae").is_err());
+    }
+
+    #[test]
+    pub fn explain_entries_of_types_and_globals() {
+        let schema = r#"
+        directive @paginated on FIELD_DEFINITION
+
+        type Query {
+          users(first: Int): [User!]! @paginated
+          invoice: Invoice
+        }
+
+        type User {
+          id: ID!
+          name: String!
+          posts: [Post!]!
+        }
+
+        type Post {
+          title: String!
+        }
+
+        type Invoice {
+          total: Float!
+        }
+
+        input UpdateUserInput {
+          name: String
+        }
+        "#;
+        let config: RendererConfig = toml::from_str(
+            r##"
+            typed_ids = true
+            maybe_undefined_types = ["UpdateUserInput"]
+            custom_member_types = ["Post"]
+
+            [primitive]
+            int = "i32"
+
+            [[type]]
+            target_type = "Invoice"
+            feature = "billing"
+
+            [[directive_mapping]]
+            directive = "paginated"
+            attribute = "#[allow(unused)]"
+
+            [[hidden_field]]
+            target_type = "User"
+            field_def = "password: String"
+            "##,
+        )
+        .unwrap();
+        let parsed = parse_schema(schema, &config).unwrap();
+        let explain = |target: &str| explain_field(&parsed, &parsed, &config, target).unwrap();
+
+        assert!(explain("UpdateUserInput.name")
+            .contains("maybe_undefined_types: contains UpdateUserInput"));
+        assert!(explain("Query.invoice")
+            .contains(r#"[[type]] target_type = "Invoice": feature = "billing""#));
+        let report = explain("Query.users");
+        assert!(report.contains(
+            r##"[[directive_mapping]] directive = "paginated": attribute = "#[allow(unused)]""##
+        ));
+        assert!(report.contains(r#"[primitive] int = "i32""#));
+        assert!(explain("User.id").contains("typed_ids: UserId is the id of User"));
+        assert!(explain("User.posts").contains("custom_member_types: contains Post"));
+        assert!(explain("User.name").ends_with("config: none\n"));
+    }
+}
@@ -9,7 +9,7 @@ use super::typ::*;
 use super::utils::SnakeCaseWithUnderscores;
 use super::RenderContext;
 use crate::config::*;
-use anyhow::Result;
+use crate::error::{Error, Result, ResultExt, Stage};
 
 use proc_macro2::{Ident, TokenStream};
 use quote::*;
@@ -33,7 +33,7 @@ impl FieldsInfo {
     }
 }
 
-struct MemberAndMethod {
+pub(crate) struct MemberAndMethod {
     pub member: Option<TokenStream>,
     pub method: Option<TokenStream>,
     pub dependencies: Vec<TokenStream>,
@@ -61,6 +61,10 @@ pub fn fields_info(
             config,
             &resolver_settings,
             custom_member_types,
+        )
+        .in_element(
+            Stage::Objects,
+            format!("{}.{}", context.parent_name()?, field.name),
         )?;
 
         if let Some(member) = member {
@@ -76,7 +80,7 @@ pub fn fields_info(
     Ok(result)
 }
 
-fn convert_field(
+pub(crate) fn convert_field(
     field: &parse::Field,
     schema: &StructuredSchema,
     render_context: &RenderContext,
@@ -113,32 +117,101 @@ pub fn field_is_method_or_member(
     resolver_settings: &Option<&HashMap<String, &ResolverSetting>>,
     custom_member_types: &HashSet<String>,
 ) -> Result<ResolverType> {
+    resolver_type_and_reason(
+        field,
+        schema,
+        render_context,
+        renderer_config,
+        resolver_settings,
+        custom_member_types,
+    )
+    .map(|(resolver_type, _)| resolver_type)
+}
+
+/// What decided whether a field is a member or a method.
+#[derive(Eq, PartialEq, Debug)]
+pub enum ResolverTypeReason {
+    /// The `[[resolver]]` entry, or `@reverse`, of the field.
+    Override(String),
+    /// The first matching `[[resolver_rule]]` that sets `resolver_type`.
+    Rule(String),
+    /// The global `resolver_type`.
+    Global,
+    /// The fields of the query and mutation types are methods.
+    Root,
+    /// The type of the field is in `custom_member_types`.
+    CustomMemberType,
+    /// Primitives and scalars without arguments are members, the others methods.
+    Heuristic,
+}
+
+impl std::fmt::Display for ResolverTypeReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResolverTypeReason::Override(entry) => write!(f, "override {}", entry),
+            ResolverTypeReason::Rule(entry) => write!(f, "rule {}", entry),
+            ResolverTypeReason::Global => write!(f, "global resolver_type"),
+            ResolverTypeReason::Root => write!(f, "field of the query or mutation root"),
+            ResolverTypeReason::CustomMemberType => write!(f, "custom_member_types"),
+            ResolverTypeReason::Heuristic => write!(
+                f,
+                "heuristic, primitives and scalars without arguments are members"
+            ),
+        }
+    }
+}
+
+pub fn resolver_type_and_reason(
+    field: &parse::Field,
+    schema: &StructuredSchema,
+    render_context: &RenderContext,
+    renderer_config: &RendererConfig,
+    resolver_settings: &Option<&HashMap<String, &ResolverSetting>>,
+    custom_member_types: &HashSet<String>,
+) -> Result<(ResolverType, ResolverTypeReason)> {
     // First check for specific overrides.
-    if let Some(field_resolver) = resolver_settings {
-        if let Some(resolver_type) = resolver_type_in_resolver_setting(&field.name, &field_resolver)
-        {
-            return Ok(resolver_type);
+    if let Some(setting) = resolver_settings.and_then(|settings| settings.get(&field.name)) {
+        if let Some(resolver_type) = &setting.resolver_type {
+            let entry = setting.entry_name();
+            let resolver_type = parse_resolver_type(resolver_type, &entry)?;
+            return Ok((resolver_type, ResolverTypeReason::Override(entry)));
+        }
+    }
+
+    // Then the rules, in order.
+    if let Some(rule) =
+        first_matching_rule(field, schema, render_context, renderer_config, |rule| {
+            rule.resolver_type.is_some()
+        })?
+    {
+        if let Some(resolver_type) = &rule.resolver_type {
+            let entry = rule.entry_name();
+            let resolver_type = parse_resolver_type(resolver_type, &entry)?;
+            return Ok((resolver_type, ResolverTypeReason::Rule(entry)));
         }
     }
 
     // Now check if there is a default setting.
-    if let Some(resolver_type) = get_default_resolver_type(&renderer_config) {
-        return Ok(resolver_type);
+    if let Some(resolver_type) = get_default_resolver_type(renderer_config)? {
+        return Ok((resolver_type, ResolverTypeReason::Global));
     }
 
     if let parse::TypeDef::Object(object) = render_context.parent {
         //TODO(tacogips) more customize if needed
-        if schema.is_query(&object.name) {
-            return Ok(ResolverType::Method);
-        } else if schema.is_mutation(&object.name) {
-            return Ok(ResolverType::Method);
+        if schema.is_query(&object.name) || schema.is_mutation(&object.name) {
+            return Ok((ResolverType::Method, ResolverTypeReason::Root));
         }
     }
 
     if field_is_a_member(field, schema, custom_member_types)? {
-        Ok(ResolverType::Field)
+        let source_type = source_type_def(&field.typ, schema)?;
+        if source_type.is_primitive() || source_type.is_scalar() || source_type.is_mapped() {
+            Ok((ResolverType::Field, ResolverTypeReason::Heuristic))
+        } else {
+            Ok((ResolverType::Field, ResolverTypeReason::CustomMemberType))
+        }
     } else {
-        Ok(ResolverType::Method)
+        Ok((ResolverType::Method, ResolverTypeReason::Heuristic))
     }
 }
 
@@ -150,23 +223,41 @@ pub enum ResolverType {
     Field,
 }
 
-fn resolver_type_in_resolver_setting(
-    field_name: &str,
-    resolver_field_setting: &HashMap<String, &ResolverSetting>,
-) -> Option<ResolverType> {
-    match resolver_field_setting.get(field_name) {
-        Some(ResolverSetting { resolver_type, .. }) => resolver_type
-            .as_ref()
-            .map(|typ| ResolverType::from_str(typ).unwrap()),
-        None => None,
-    }
+fn parse_resolver_type(resolver_type: &str, entry: &str) -> Result<ResolverType> {
+    ResolverType::from_str(resolver_type).map_err(|_| {
+        Error::config_entry(
+            entry,
+            format!(
+                "invalid resolver_type \"{}\", expected \"method\" or \"field\"",
+                resolver_type
+            ),
+        )
+    })
 }
 
-fn get_default_resolver_type(renderer_config: &RendererConfig) -> Option<ResolverType> {
-    match &renderer_config.resolver_type {
-        Some(resolver_type) => Some(ResolverType::from_str(resolver_type).unwrap()),
-        None => None,
+/// The first `[[resolver_rule]]` matching the field among those `sets` holds for.
+fn first_matching_rule<'a>(
+    field: &parse::Field,
+    schema: &StructuredSchema,
+    render_context: &RenderContext,
+    renderer_config: &'a RendererConfig,
+    sets: impl Fn(&ResolverRule) -> bool,
+) -> Result<Option<&'a ResolverRule>> {
+    let parent_name = render_context.parent_name()?;
+    for rule in renderer_config.resolver_rule.iter().flatten() {
+        if sets(rule) && rule.matches(&parent_name, field, &schema.definitions)? {
+            return Ok(Some(rule));
+        }
     }
+    Ok(None)
+}
+
+fn get_default_resolver_type(renderer_config: &RendererConfig) -> Result<Option<ResolverType>> {
+    renderer_config
+        .resolver_type
+        .as_ref()
+        .map(|resolver_type| parse_resolver_type(resolver_type, "resolver_type"))
+        .transpose()
 }
 
 /// default:
@@ -184,7 +275,7 @@ fn field_is_a_member(
         } else {
             Ok(false)
         }
-    } else if let parse::TypeDef::Scalar(_) = source_type {
+    } else if source_type.is_scalar() || source_type.is_mapped() {
         if field.arguments.is_empty() {
             Ok(true)
         } else {
@@ -200,15 +291,26 @@ fn field_is_a_member(
 }
 
 fn get_attribute_from_resolver_settings(
-    field_name: &str,
+    field: &parse::Field,
+    schema: &StructuredSchema,
+    context: &RenderContext,
     resolver_settings: &Option<&HashMap<String, &ResolverSetting>>,
-) -> Option<String> {
+) -> Result<TokenStream> {
     if let Some(field_resolver) = resolver_settings {
-        if let Some(resolver_sertting) = field_resolver.get(field_name) {
-            return resolver_sertting.attribute.clone();
+        if let Some(resolver_sertting) = field_resolver.get(&field.name) {
+            if let Some(attribute) = &resolver_sertting.attribute {
+                return config_snippet_token(attribute, &resolver_sertting.entry_name());
+            }
+        }
+    }
+    if let Some(rule) = first_matching_rule(field, schema, context, context.config, |rule| {
+        rule.attribute.is_some()
+    })? {
+        if let Some(attribute) = &rule.attribute {
+            return config_snippet_token(attribute, &rule.entry_name());
         }
     }
-    return None;
+    Ok(quote! {})
 }
 
 /// return resolver method
@@ -225,7 +327,7 @@ fn resolver_with_member(
     resolver_settings: &Option<&HashMap<String, &ResolverSetting>>,
 ) -> Result<MemberAndMethod> {
     let (name, old_name) = field_or_member_name(field);
-    let typ = value_type_def_token(&field.typ, &schema, &context)?;
+    let typ = field_type_token(&field.typ, field.primitive_type.as_ref(), schema, context)?;
 
     // Handle field names that cannot use `r#`, such as `self`.
     let field_attribute = match old_name {
@@ -236,28 +338,25 @@ fn resolver_with_member(
         None => quote! {},
     };
 
-    let member = Some(quote! { #field_attribute pub #name :#typ });
+    let cfg = cfg_token(&field.features);
+    let directive_attributes = directive_attributes_token(&field.directives, context.config)?;
+    let member = Some(quote! { #cfg #field_attribute #directive_attributes pub #name :#typ });
 
-    let attribute = match get_attribute_from_resolver_settings(&field.name, resolver_settings) {
-        Some(attribute) => {
-            let attr = attribute.parse::<TokenStream>().unwrap();
-            quote! {
-                #attr
-            }
-        }
-        None => quote! {},
-    };
+    let attribute =
+        get_attribute_from_resolver_settings(field, schema, context, resolver_settings)?;
     let field_rustdoc = match &field.description {
         Some(desc_token) => to_rust_docs_token(desc_token),
         None => quote! {},
     };
 
     let member_need_clone = if let ValueTypeDef::Named(typ) = &field.typ {
-        let type_def = typ.as_type_def(&schema.definitions).unwrap();
+        let type_def = typ.as_type_def(&schema.definitions)?;
         match type_def {
-            TypeDef::Primitive(PrimitiveKind::Int)
-            | TypeDef::Primitive(PrimitiveKind::Float)
-            | TypeDef::Primitive(PrimitiveKind::Boolean) => false,
+            TypeDef::Primitive(primitive) => !is_copy_type(&primitive_rust_type(
+                primitive,
+                field.primitive_type.as_ref(),
+                context,
+            )),
             _ => true,
         }
     } else {
@@ -270,9 +369,15 @@ fn resolver_with_member(
         quote! { self.#name }
     };
 
+    let attributes = merge_graphql_attributes(
+        attribute,
+        field_graphql_arguments(field),
+        &format!("{}.{}", context.parent_name()?, field.name),
+    )?;
     let method = Some(quote! {
         #field_rustdoc
-        #attribute
+        #cfg
+        #attributes
         pub async fn #name(&self) -> #typ  {
             #resolver_body
         }
@@ -291,6 +396,7 @@ pub fn args_defs_and_values(
     field: &parse::Field,
     schema: &StructuredSchema,
     name_prefix: &str,
+    with_attributes: bool,
     context: &RenderContext,
 ) -> Result<(TokenStream, TokenStream)> {
     if field.arguments.is_empty() {
@@ -299,7 +405,9 @@ pub fn args_defs_and_values(
         let arg_defs = field
             .arguments
             .iter()
-            .map(|argument| argument_def_token(argument, &schema, &name_prefix, &context))
+            .map(|argument| {
+                argument_def_token(argument, schema, name_prefix, with_attributes, context)
+            })
             .collect::<Result<Vec<TokenStream>>>()?;
         let arg_defs = separate_by_comma(arg_defs);
 
@@ -332,37 +440,40 @@ fn resolver_with_datasource(
     renderer_config: &RendererConfig,
     resolver_settings: &Option<&HashMap<String, &ResolverSetting>>,
 ) -> Result<MemberAndMethod> {
-    let (arg_defs, arg_values) = args_defs_and_values(&field, &schema, "", &context)?;
+    let (arg_defs, arg_values) = args_defs_and_values(field, schema, "", true, context)?;
 
     let (field_name, _old_name) = field_or_member_name(field);
     let resolver_method_name = format_ident!(
         "{}",
-        format!("{}_{}", context.parent_name(), field.name_string())
+        format!("{}_{}", context.parent_name()?, field.name_string())
             .to_snake_case_with_underscores()
     );
 
-    let attribute = match get_attribute_from_resolver_settings(&field.name, resolver_settings) {
-        Some(attribute) => {
-            let attr = attribute.parse::<TokenStream>().unwrap();
-            quote! {
-                #attr
-            }
-        }
-        None => quote! {},
-    };
+    let attribute =
+        get_attribute_from_resolver_settings(field, schema, context, resolver_settings)?;
 
     let field_rustdoc = match &field.description {
         Some(desc_token) => to_rust_docs_token(desc_token),
         None => quote! {},
     };
 
-    let typ = value_type_def_token(&field.typ, &schema, &context)?;
+    let typ = field_type_token(&field.typ, field.primitive_type.as_ref(), schema, context)?;
     let typ: TokenStream = quote! {Result<#typ>};
-    let data_source_fetch_method: TokenStream =
-        renderer_config.data_source_fetch_method.parse().unwrap();
+    let data_source_fetch_method = config_snippet_token(
+        &renderer_config.data_source_fetch_method,
+        "data_source_fetch_method",
+    )?;
+    let cfg = cfg_token(&field.features);
+    let directive_attributes = directive_attributes_token(&field.directives, context.config)?;
+    let attributes = merge_graphql_attributes(
+        quote! { #attribute #directive_attributes },
+        field_graphql_arguments(field),
+        &format!("{}.{}", context.parent_name()?, field.name),
+    )?;
     let method = quote! {
         #field_rustdoc
-        #attribute
+        #cfg
+        #attributes
         pub async fn #field_name(&self, ctx: &Context<'_> #arg_defs ) -> #typ {
             #data_source_fetch_method.#resolver_method_name (ctx, self #arg_values).await
         }
@@ -382,6 +493,15 @@ fn resolver_with_datasource(
     })
 }
 
+/// The `#[graphql(..)]` arguments of a resolver method with the visibility and the cache hints of the field.
+fn field_graphql_arguments(field: &parse::Field) -> Vec<TokenStream> {
+    let mut arguments: Vec<TokenStream> = field.visible.iter().map(visible_token).collect();
+    if let Some(cache_control) = &field.cache_control {
+        arguments.push(cache_control_token(cache_control));
+    }
+    arguments
+}
+
 /// Returns Some for the second element if the field was renamed. Otherwise, returns None.
 fn field_or_member_name(field: &parse::Field) -> (Ident, Option<String>) {
     let field_name: String = field.name_string().to_snake_case_with_underscores().into();
@@ -393,3 +513,94 @@ fn field_or_member_name(field: &parse::Field) -> (Ident, Option<String>) {
         (format_ident!("{}", field_name), None)
     }
 }
+
+#[cfg(test)]
+mod test {
+
+    use super::super::{attribute, render_for_test, syntax, ParsedSource};
+    use crate::RendererConfig;
+
+    #[test]
+    pub fn render_resolver_rules() {
+        let schema = r#"
+        schema {
+          query: Query
+        }
+
+        type Query {
+          users: UserConnection!
+        }
+
+        type UserConnection {
+          edges: [UserEdge!]!
+          totalCount: Int!
+        }
+
+        type UserEdge {
+          cursor: String!
+          node: User!
+        }
+
+        type User {
+          name: String!
+          email: String @auth
+          friends(first: Int): [User!]!
+        }
+        "#;
+        let (files, _) = render_for_test(
+            schema,
+            r##"
+            [[resolver_rule]]
+            parent_type = "*Connection"
+            field = "edges"
+            resolver_type = "field"
+
+            [[resolver_rule]]
+            directive = "auth"
+            attribute = "#[allow(unused)]"
+
+            [[resolver_rule]]
+            parent_type = "/^User(Edge)?$/"
+            return_kind = "object"
+            resolver_type = "field"
+
+            [[resolver_rule]]
+            parent_type = "User"
+            resolver_type = "method"
+
+            [[resolver]]
+            target_type = "User"
+            target_field = "name"
+            resolver_type = "field"
+            "##,
+        )
+        .unwrap();
+
+        let objects = ParsedSource::of(&files, "objects.rs");
+        assert_eq!(
+            objects.field_names("UserConnection"),
+            vec!["edges", "total_count"]
+        );
+        assert_eq!(
+            objects.field("UserConnection", "edges").ty,
+            syntax("Vec<UserEdge>")
+        );
+        assert_eq!(objects.field_names("UserEdge"), vec!["cursor", "node"]);
+        assert_eq!(objects.field_names("User"), vec!["name", "friends"]);
+        let email = objects.method("User", "email");
+        assert_eq!(email.attrs, vec![attribute("#[allow(unused)]")]);
+        assert_eq!(
+            email.sig,
+            syntax("async fn email(&self, ctx: &Context<'_>) -> Result<Option<String>>")
+        );
+
+        let error = toml::from_str::<RendererConfig>(
+            r#"
+            [[resolver_rule]]
+            parent_type = "/(/"
+            "#,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("regex parse error"));
+    }
+}
@@ -1,16 +1,35 @@
-use anyhow::Result;
+use crate::error::{Error, Result};
+use std::io::Write;
 use std::path::PathBuf;
-use std::process::Command;
+use std::process::{Command, Stdio};
 
-pub fn fmt_file<P: AsRef<str>>(file_path: P) -> Result<()> {
-    let output = Command::new("rustfmt")
+/// Format the source with rustfmt. `file_name` is only used to name the file in errors.
+pub fn fmt_source(file_name: &str, source: &str) -> Result<String> {
+    let format_error = |message: String| Error::Format {
+        path: file_name.to_string(),
+        message,
+    };
+    let mut rustfmt = Command::new("rustfmt")
         .arg("--edition=2018")
         .arg("--config=normalize_doc_attributes=true")
-        .arg(file_path.as_ref())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
         .spawn()
-        .expect("rustfmt failed");
-    output.wait_with_output()?;
-    Ok(())
+        .map_err(|e| format_error(e.to_string()))?;
+
+    // rustfmt reads the whole input before writing, so the stdout pipe can not fill up here.
+    if let Some(mut stdin) = rustfmt.stdin.take() {
+        stdin.write_all(source.as_bytes())?;
+    }
+
+    let output = rustfmt.wait_with_output()?;
+    if !output.status.success() {
+        return Err(format_error(
+            String::from_utf8_lossy(&output.stderr).to_string(),
+        ));
+    }
+    String::from_utf8(output.stdout).map_err(|e| format_error(e.to_string()))
 }
 
 pub fn pathbuf_to_str(pathbuf: &PathBuf) -> String {
@@ -0,0 +1,486 @@
+use super::super::parse::*;
+use super::files::{fmt_source, pathbuf_to_str};
+use super::*;
+use crate::config::{Phase, RendererConfig};
+use crate::error::{Error, Result};
+use crate::validation::check_snippets;
+use std::borrow::Cow;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A generated source file. `path` is relative to the output directory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratedFile {
+    pub path: PathBuf,
+    pub source: String,
+}
+
+/// The files generated from a schema, in the order they were rendered.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GeneratedFiles {
+    files: Vec<GeneratedFile>,
+}
+
+impl GeneratedFiles {
+    pub fn push<P: Into<PathBuf>>(&mut self, path: P, source: String) {
+        self.files.push(GeneratedFile {
+            path: path.into(),
+            source,
+        });
+    }
+
+    pub fn get<P: AsRef<Path>>(&self, path: P) -> Option<&str> {
+        self.files
+            .iter()
+            .find(|file| file.path == path.as_ref())
+            .map(|file| file.source.as_str())
+    }
+
+    pub fn contains<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.get(path).is_some()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, GeneratedFile> {
+        self.files.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Write all the files under `output_dir`, replacing the existing ones.
+    /// Files whose content did not change are left untouched so their timestamps are kept.
+    pub fn write_to(&self, output_dir: &str) -> Result<()> {
+        for file in self.files.iter() {
+            let mut output_file = PathBuf::from(output_dir);
+            output_file.push(&file.path);
+            if let Ok(current) = fs::read_to_string(&output_file) {
+                if current == file.source {
+                    continue;
+                }
+            }
+            fs::write(&output_file, &file.source)
+                .map_err(|e| Error::file(pathbuf_to_str(&output_file), e))?;
+            log::info!("updated {}", pathbuf_to_str(&output_file));
+        }
+        Ok(())
+    }
+}
+
+impl IntoIterator for GeneratedFiles {
+    type Item = GeneratedFile;
+    type IntoIter = std::vec::IntoIter<GeneratedFile>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.files.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a GeneratedFiles {
+    type Item = &'a GeneratedFile;
+    type IntoIter = std::slice::Iter<'a, GeneratedFile>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.files.iter()
+    }
+}
+
+/// How the generated `mod.rs` pulls in the file of each phase.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModuleLayout {
+    /// `mod objects;`, for files written next to the `mod.rs`.
+    Files,
+    /// `mod objects { include!("<dir>/objects.rs"); }`, for files generated outside of the source tree
+    /// such as `OUT_DIR`. `dir` should be absolute.
+    Include(PathBuf),
+    /// `mod objects { ... }`, every phase is embedded in the `mod.rs` and it is the only file generated.
+    Inline,
+}
+
+/// Renders the rust sources in memory. `output_schema` and `output_datasource` write what this returns.
+///
+///```ignore
+/// let files = Generator::new(&structured_schema, &config).schema_files()?;
+/// for file in files.iter() {
+///     println!("{}: {} bytes", file.path.display(), file.source.len());
+/// }
+///```
+pub struct Generator<'a> {
+    schema: &'a StructuredSchema,
+    /// The config with the `@reverse` settings of the schema.
+    config: Cow<'a, RendererConfig>,
+    layout: ModuleLayout,
+    format: bool,
+}
+
+impl<'a> Generator<'a> {
+    pub fn new(schema: &'a StructuredSchema, config: &'a RendererConfig) -> Self {
+        Self {
+            schema,
+            config: config.with_inline_settings(&schema.inline_settings),
+            layout: ModuleLayout::Files,
+            format: true,
+        }
+    }
+
+    /// Whether to run rustfmt on the sources. Enabled by default.
+    pub fn format(mut self, format: bool) -> Self {
+        self.format = format;
+        self
+    }
+
+    fn fmt_source(&self, file_name: &str, source: String) -> Result<String> {
+        if self.format {
+            fmt_source(file_name, &source)
+        } else {
+            Ok(source)
+        }
+    }
+
+    pub fn module_layout(mut self, layout: ModuleLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// The files of every enabled phase and the `mod.rs` that ties them together.
+    pub fn schema_files(&self) -> Result<GeneratedFiles> {
+        check_snippets(&self.config)?;
+        let mut files = GeneratedFiles::default();
+        let mut written_phases = Vec::<(Phase, String)>::new();
+
+        for phase in Phase::all() {
+            if !self.config.phase_enabled(&phase) {
+                continue;
+            }
+
+            let source = match phase {
+                Phase::Objects => objects::objects_source(self.schema, &self.config)?,
+                Phase::InputObjects => {
+                    input_objects::input_objects_source(self.schema, &self.config)?
+                }
+                Phase::Unions => unions::unions_source(self.schema, &self.config)?,
+                Phase::Scalars => scalars::scalars_source(self.schema, &self.config)?,
+                Phase::Interfaces => interfaces::interfaces_source(self.schema, &self.config)?,
+                Phase::Enums => enums::enums_source(self.schema, &self.config)?,
+                Phase::Directives => directives::directives_source(self.schema, &self.config)?,
+            };
+
+            if let Some(source) = source {
+                if self.layout != ModuleLayout::Inline {
+                    let file_name = phase.file_name();
+                    files.push(&file_name, self.fmt_source(&file_name, source.clone())?);
+                }
+                written_phases.push((phase, source));
+            }
+        }
+
+        let mod_source = schema_mod_source(&written_phases, &self.layout, self.schema)?;
+        files.push("mod.rs", self.fmt_source("mod.rs", mod_source)?);
+
+        Ok(files)
+    }
+
+    /// The `mod.rs` holding the `DataSource` with an empty method for each resolver.
+    pub fn datasource_files(&self) -> Result<GeneratedFiles> {
+        check_snippets(&self.config)?;
+        let mut files = GeneratedFiles::default();
+        let source = datasource_mod_source(self.schema, &self.config)?;
+        files.push("mod.rs", self.fmt_source("mod.rs", source)?);
+        Ok(files)
+    }
+}
+
+/// Renders the schema and the datasource files of `schema` with the toml `config`, for the
+/// renderer tests.
+#[cfg(test)]
+pub(crate) fn render_for_test(
+    schema: &str,
+    config: &str,
+) -> Result<(GeneratedFiles, GeneratedFiles)> {
+    let config: RendererConfig = toml::from_str(config).unwrap();
+    let structured_schema = parse_schema(schema, &config)?;
+    let generator = Generator::new(&structured_schema, &config);
+    Ok((generator.schema_files()?, generator.datasource_files()?))
+}
+
+/// A generated file parsed with syn, for the renderer tests to look up its items.
+#[cfg(test)]
+pub(crate) struct ParsedSource(syn::File);
+
+#[cfg(test)]
+impl ParsedSource {
+    pub fn of(files: &GeneratedFiles, path: &str) -> Self {
+        let source = files
+            .get(path)
+            .unwrap_or_else(|| panic!("{} is not generated", path));
+        Self(syn::parse_file(source).unwrap())
+    }
+
+    pub fn uses(&self) -> Vec<&syn::ItemUse> {
+        self.0
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                syn::Item::Use(item) => Some(item),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn has_use(&self, expected: &str) -> bool {
+        let expected: syn::ItemUse = syntax(expected);
+        self.uses().into_iter().any(|item| *item == expected)
+    }
+
+    pub fn struct_names(&self) -> Vec<String> {
+        self.0
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                syn::Item::Struct(item) => Some(item.ident.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn function_names(&self) -> Vec<String> {
+        self.0
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                syn::Item::Fn(item) => Some(item.sig.ident.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn structure(&self, name: &str) -> &syn::ItemStruct {
+        self.0
+            .items
+            .iter()
+            .find_map(|item| match item {
+                syn::Item::Struct(item) if item.ident == name => Some(item),
+                _ => None,
+            })
+            .unwrap_or_else(|| panic!("no struct {}", name))
+    }
+
+    pub fn field(&self, struct_name: &str, field_name: &str) -> &syn::Field {
+        self.structure(struct_name)
+            .fields
+            .iter()
+            .find(|field| {
+                field
+                    .ident
+                    .as_ref()
+                    .map_or(false, |ident| ident == field_name)
+            })
+            .unwrap_or_else(|| panic!("no field {} in {}", field_name, struct_name))
+    }
+
+    pub fn enumeration(&self, name: &str) -> &syn::ItemEnum {
+        self.0
+            .items
+            .iter()
+            .find_map(|item| match item {
+                syn::Item::Enum(item) if item.ident == name => Some(item),
+                _ => None,
+            })
+            .unwrap_or_else(|| panic!("no enum {}", name))
+    }
+
+    pub fn variant(&self, enum_name: &str, variant_name: &str) -> &syn::Variant {
+        self.enumeration(enum_name)
+            .variants
+            .iter()
+            .find(|variant| variant.ident == variant_name)
+            .unwrap_or_else(|| panic!("no variant {} in {}", variant_name, enum_name))
+    }
+
+    pub fn field_names(&self, struct_name: &str) -> Vec<String> {
+        self.structure(struct_name)
+            .fields
+            .iter()
+            .filter_map(|field| field.ident.as_ref().map(|ident| ident.to_string()))
+            .collect()
+    }
+
+    /// The impl blocks of `type_name`, inherent and trait impls alike.
+    pub fn impls(&self, type_name: &str) -> Vec<&syn::ItemImpl> {
+        self.0
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                syn::Item::Impl(item) => match item.self_ty.as_ref() {
+                    syn::Type::Path(path) if path.path.is_ident(type_name) => Some(item),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn method(&self, type_name: &str, method_name: &str) -> &syn::ImplItemMethod {
+        self.impls(type_name)
+            .into_iter()
+            .flat_map(|item| item.items.iter())
+            .find_map(|item| match item {
+                syn::ImplItem::Method(method) if method.sig.ident == method_name => Some(method),
+                _ => None,
+            })
+            .unwrap_or_else(|| panic!("no method {} of {}", method_name, type_name))
+    }
+
+    pub fn function(&self, name: &str) -> &syn::ItemFn {
+        self.0
+            .items
+            .iter()
+            .find_map(|item| match item {
+                syn::Item::Fn(item) if item.sig.ident == name => Some(item),
+                _ => None,
+            })
+            .unwrap_or_else(|| panic!("no function {}", name))
+    }
+}
+
+/// Parses `source` to compare it with what a `ParsedSource` holds.
+#[cfg(test)]
+pub(crate) fn syntax<T: syn::parse::Parse>(source: &str) -> T {
+    syn::parse_str(source).unwrap_or_else(|e| panic!("{}: {}", source, e))
+}
+
+/// Parses an outer attribute such as `#[graphql(secret)]`.
+#[cfg(test)]
+pub(crate) fn attribute(source: &str) -> syn::Attribute {
+    use syn::parse::Parser;
+    let mut attributes = syn::Attribute::parse_outer
+        .parse_str(source)
+        .unwrap_or_else(|e| panic!("{}: {}", source, e));
+    assert_eq!(attributes.len(), 1, "{}", source);
+    attributes.remove(0)
+}
+
+/// The argument `name` of a generated function or method.
+#[cfg(test)]
+pub(crate) fn argument<'a>(signature: &'a syn::Signature, name: &str) -> &'a syn::PatType {
+    signature
+        .inputs
+        .iter()
+        .find_map(|input| match input {
+            syn::FnArg::Typed(arg) => match arg.pat.as_ref() {
+                syn::Pat::Ident(pat) if pat.ident == name => Some(arg),
+                _ => None,
+            },
+            _ => None,
+        })
+        .unwrap_or_else(|| panic!("no argument {} in {}", name, signature.ident))
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    pub fn generate_schema_files_in_memory() {
+        let schema = r#"
+        schema {
+          query: Query
+        }
+
+        type Query {
+          active: Boolean!
+        }
+
+        enum Status {
+          REGISTERED
+        }
+        "#;
+        let config = RendererConfig::default();
+        let structured_schema = parse_schema(schema, &config).unwrap();
+        let files = Generator::new(&structured_schema, &config)
+            .schema_files()
+            .unwrap();
+
+        let paths: Vec<String> = files
+            .iter()
+            .map(|file| file.path.display().to_string())
+            .collect();
+        assert_eq!(paths, vec!["objects.rs", "enums.rs", "mod.rs"]);
+
+        let mod_source = files.get("mod.rs").unwrap();
+        assert!(mod_source.contains("mod objects;"));
+        assert!(mod_source.contains("mod enums;"));
+        assert!(!mod_source.contains("mod unions;"));
+        assert!(files
+            .get("objects.rs")
+            .unwrap()
+            .contains("pub struct Query {}"));
+    }
+
+    #[test]
+    pub fn render_inline_settings() {
+        let schema = r##"
+        schema {
+          query: Query
+        }
+
+        type Query {
+          me: User! @reverse(resolver: "field")
+          count: Int! @reverse(rustType: "u32")
+          total: Int! @reverse(rustType: "u32")
+        }
+
+        type User {
+          name: String! @reverse(resolver: "method", attribute: "#[allow(unused)]")
+        }
+
+        enum Status @reverse(renameItems: "lowercase") {
+          ACTIVE @reverse(rename: "enabled")
+          DELETED
+        }
+        "##;
+        let (files, _) = render_for_test(
+            schema,
+            r#"
+            [[field]]
+            target_type = "Query"
+            target_field = "total"
+            primitive_type = "u64"
+            "#,
+        )
+        .unwrap();
+
+        let objects = ParsedSource::of(&files, "objects.rs");
+        assert_eq!(
+            *objects.structure("Query"),
+            syntax("#[derive(Debug, Clone)] pub struct Query { pub me: User, }")
+        );
+        assert_eq!(
+            objects.method("Query", "count").sig.output,
+            syntax("-> Result<u32>")
+        );
+        assert_eq!(
+            objects.method("Query", "total").sig.output,
+            syntax("-> Result<u64>")
+        );
+        assert_eq!(
+            objects.method("User", "name").attrs,
+            vec![attribute("#[allow(unused)]")]
+        );
+        let enums = ParsedSource::of(&files, "enums.rs");
+        assert!(enums
+            .enumeration("Status")
+            .attrs
+            .contains(&attribute("#[graphql(rename_items = \"lowercase\")]")));
+        assert_eq!(
+            enums.variant("Status", "Active").attrs,
+            vec![attribute("#[graphql(name = \"enabled\")]")]
+        );
+        assert!(enums.variant("Status", "Deleted").attrs.is_empty());
+    }
+}
@@ -2,10 +2,13 @@ use super::super::parse::{self, *};
 use super::dependencies::*;
 use super::keywords::*;
 use super::sorter::sort_by_line_pos_and_name;
+use super::tokens::{
+    cfg_token, directive_attributes_token, graphql_attribute_token, visible_token,
+};
 use super::typ::*;
 use super::utils::SnakeCaseWithUnderscores;
 use super::RenderContext;
-use anyhow::Result;
+use crate::error::Result;
 use proc_macro2::{Ident, TokenStream};
 use quote::*;
 //use syn::*;
@@ -23,7 +26,7 @@ impl InputFieldsInfo {
     }
 }
 
-struct InputMember {
+pub(crate) struct InputMember {
     pub member: TokenStream,
     pub dependencies: Vec<TokenStream>,
 }
@@ -48,14 +51,29 @@ pub fn input_fields_info(
     Ok(result)
 }
 
-fn convert_input_field(
+pub(crate) fn convert_input_field(
     field: &parse::InputField,
     schema: &StructuredSchema,
     render_context: &RenderContext,
 ) -> Result<InputMember> {
     let name = input_field_name(field);
-    let typ = value_type_def_token(&field.typ, &schema, &render_context)?;
-    let member = quote! { pub #name :#typ };
+    let typ = input_value_type_token(
+        &field.typ,
+        field.primitive_type.as_ref(),
+        field.maybe_undefined,
+        schema,
+        render_context,
+    )?;
+    let mut attribute_arguments: Vec<TokenStream> =
+        field.visible.iter().map(visible_token).collect();
+    if field.secret {
+        attribute_arguments.push(quote! { secret });
+    }
+    let attribute = graphql_attribute_token(attribute_arguments);
+    let cfg = cfg_token(&field.features);
+    let directive_attributes =
+        directive_attributes_token(&field.directives, render_context.config)?;
+    let member = quote! { #cfg #attribute #directive_attributes pub #name :#typ };
 
     let dependencies = dependency(&field.typ, schema, render_context)?;
 
@@ -1,28 +1,19 @@
 use super::super::parse::*;
 use super::comment::*;
 use super::dependencies::*;
-use super::files::{fmt_file, pathbuf_to_str};
 use super::input_fields::*;
 use super::sorter::sort_by_line_pos_and_name;
 use super::tokens::*;
 use super::RenderContext;
-use anyhow::Result;
+use crate::config::{Phase, RendererConfig};
+use crate::error::{Result, ResultExt, Stage};
 use proc_macro2::TokenStream;
 use quote::*;
-use std::collections::HashSet;
-use std::fs::{self, OpenOptions};
-use std::io::{BufWriter, Write};
-use std::path::PathBuf;
-
-pub fn write_input_objects(output_dir: &str, structured_schema: &StructuredSchema) -> Result<bool> {
-    let mut output_file = PathBuf::from(output_dir);
-    output_file.push("input_objects.rs");
-    let file_path_str = pathbuf_to_str(&output_file);
-
-    if output_file.exists() {
-        fs::remove_file(&file_path_str)?;
-    }
 
+pub fn input_objects_source(
+    structured_schema: &StructuredSchema,
+    config: &RendererConfig,
+) -> Result<Option<String>> {
     let mut input_objects: Vec<&InputObject> = structured_schema
         .definitions
         .input_objects
@@ -30,15 +21,16 @@ pub fn write_input_objects(output_dir: &str, structured_schema: &StructuredSchem
         .into_iter()
         .collect();
     if input_objects.is_empty() {
-        return Ok(false);
+        return Ok(None);
     }
     input_objects.sort_by(sort_by_line_pos_and_name);
 
-    let mut all_dependencies = HashSet::<String>::new();
+    let mut all_dependencies = config_dependencies(config, Phase::InputObjects.module_name())?;
     let mut object_defs = Vec::<String>::new();
 
     for each_obj in input_objects {
-        let (object_token, dependencies) = input_object_token(each_obj, &structured_schema)?;
+        let (object_token, dependencies) = input_object_token(each_obj, structured_schema, config)
+            .in_element(Stage::InputObjects, &each_obj.name)?;
 
         object_defs.push(object_token.to_string());
 
@@ -47,34 +39,27 @@ pub fn write_input_objects(output_dir: &str, structured_schema: &StructuredSchem
         }
     }
 
-    let dest_file = OpenOptions::new()
-        .write(true)
-        .create(true)
-        .open(output_file.as_path())
-        .expect(format!("failed to open file : {}", file_path_str).as_ref());
-    let mut dest_file = BufWriter::new(dest_file);
-
-    dest_file.write(FILE_HEADER_COMMENT.as_bytes())?;
+    let mut source = String::new();
+    source.push_str(FILE_HEADER_COMMENT);
     let header = quote! {
         use async_graphql::*;
     };
 
-    dest_file.write(header.to_string().as_bytes())?;
+    source.push_str(&header.to_string());
     let dependencies_token = dependency_strs_to_token(all_dependencies);
 
-    dest_file.write(dependencies_token.to_string().as_bytes())?;
+    source.push_str(&dependencies_token.to_string());
     for each_obj_def in object_defs {
-        dest_file.write(each_obj_def.as_bytes())?;
+        source.push_str(&each_obj_def);
     }
 
-    dest_file.flush()?;
-    fmt_file(file_path_str)?;
-    Ok(true)
+    Ok(Some(source))
 }
 
 fn input_object_token(
     input_object: &InputObject,
     schema: &StructuredSchema,
+    config: &RendererConfig,
 ) -> Result<(TokenStream, Vec<TokenStream>)> {
     let object_name = format_ident!("{}", input_object.name);
     let comment = match &input_object.description {
@@ -84,6 +69,7 @@ fn input_object_token(
 
     let context = RenderContext {
         parent: TypeDef::InputObject(input_object),
+        config,
     };
 
     let InputFieldsInfo {
@@ -92,9 +78,16 @@ fn input_object_token(
     } = input_fields_info(input_object.fields.iter().collect(), schema, &context)?;
 
     let members = separate_by_comma(members);
+    let visible_attribute =
+        graphql_attribute_token(input_object.visible.iter().map(visible_token).collect());
+    let cfg = cfg_token(&input_object.features);
+    let directive_attributes = directive_attributes_token(&input_object.directives, config)?;
     let object_def = quote! {
         #comment
+        #cfg
         #[derive(InputObject)]
+        #visible_attribute
+        #directive_attributes
         pub struct #object_name{
             #members
         }
@@ -129,7 +122,8 @@ mod test {
         assert_eq!(1, input_objects.len());
         let input_object = input_objects.remove(0);
         let (object_token, _dependencies) =
-            input_object_token(input_object, &structured_schema).unwrap();
+            input_object_token(input_object, &structured_schema, &RendererConfig::default())
+                .unwrap();
 
         let expected = r#"
     #[derive(InputObject)]
@@ -163,7 +157,8 @@ mod test {
         assert_eq!(1, input_objects.len());
         let input_object = input_objects.remove(0);
         let (object_token, _dependencies) =
-            input_object_token(input_object, &structured_schema).unwrap();
+            input_object_token(input_object, &structured_schema, &RendererConfig::default())
+                .unwrap();
 
         let expected = r#"
     #[derive(InputObject)]
@@ -197,7 +192,8 @@ mod test {
         assert_eq!(1, input_objects.len());
         let input_object = input_objects.remove(0);
         let (object_token, _dependencies) =
-            input_object_token(input_object, &structured_schema).unwrap();
+            input_object_token(input_object, &structured_schema, &RendererConfig::default())
+                .unwrap();
 
         let expected = r#"
     #[derive(InputObject)]
@@ -1,36 +1,23 @@
 use super::super::parse::*;
 use super::comment::*;
 use super::dependencies::*;
-use super::files::{fmt_file, pathbuf_to_str};
 use super::sorter::sort_by_line_pos_and_name;
 use super::tokens::*;
 use super::typ::*;
+use super::unions::member_features;
 use super::utils::SnakeCaseWithUnderscores;
 use super::RenderContext;
-use crate::config::RendererConfig;
-use anyhow::Result;
+use crate::config::{Phase, RendererConfig};
+use crate::error::{Result, ResultExt, Stage};
 use heck::CamelCase;
 use proc_macro2::TokenStream;
 use quote::*;
 use std::collections::HashMap;
-use std::collections::HashSet;
-use std::fs::{self, OpenOptions};
-use std::io::{BufWriter, Write};
-use std::path::PathBuf;
 
-pub fn write_interfaces(
-    output_dir: &str,
+pub fn interfaces_source(
     structured_schema: &StructuredSchema,
     render_config: &RendererConfig,
-) -> Result<bool> {
-    let mut output_file = PathBuf::from(output_dir);
-    output_file.push("interfaces.rs");
-    let file_path_str = pathbuf_to_str(&output_file);
-
-    if output_file.exists() {
-        fs::remove_file(&file_path_str)?;
-    }
-
+) -> Result<Option<String>> {
     let mut interfaces: Vec<&Interface> = structured_schema
         .definitions
         .interfaces
@@ -38,11 +25,11 @@ pub fn write_interfaces(
         .into_iter()
         .collect();
     if interfaces.is_empty() {
-        return Ok(false);
+        return Ok(None);
     }
     interfaces.sort_by(sort_by_line_pos_and_name);
 
-    let mut all_dependencies = HashSet::<String>::new();
+    let mut all_dependencies = config_dependencies(render_config, Phase::Interfaces.module_name())?;
     let mut interface_defs = Vec::<String>::new();
 
     let interface_and_impl_types = find_implment_types_by_interface_type(&structured_schema);
@@ -53,7 +40,8 @@ pub fn write_interfaces(
             &structured_schema,
             render_config,
             &interface_and_impl_types,
-        )?;
+        )
+        .in_element(Stage::Interfaces, &each_obj.name)?;
 
         interface_defs.push(interface_token.to_string());
 
@@ -62,58 +50,64 @@ pub fn write_interfaces(
         }
     }
 
-    let dest_file = OpenOptions::new()
-        .write(true)
-        .create(true)
-        .open(output_file.as_path())
-        .expect(format!("failed to open file : {}", file_path_str).as_ref());
-    let mut dest_file = BufWriter::new(dest_file);
-
-    dest_file.write(FILE_HEADER_COMMENT.as_bytes())?;
+    let mut source = String::new();
+    source.push_str(FILE_HEADER_COMMENT);
     let header = quote! {
         use async_graphql::*;
     };
 
-    dest_file.write(header.to_string().as_bytes())?;
+    source.push_str(&header.to_string());
     let dependencies_token = dependency_strs_to_token(all_dependencies);
 
-    dest_file.write(dependencies_token.to_string().as_bytes())?;
+    source.push_str(&dependencies_token.to_string());
     for each_obj_def in interface_defs {
-        dest_file.write(each_obj_def.as_bytes())?;
+        source.push_str(&each_obj_def);
     }
 
-    dest_file.flush()?;
-    fmt_file(file_path_str)?;
-    Ok(true)
+    Ok(Some(source))
 }
 
 fn interface_token(
     interface: &Interface,
     schema: &StructuredSchema,
-    _render_config: &RendererConfig,
+    render_config: &RendererConfig,
     interface_type_and_impl_types: &HashMap<String, Vec<String>>,
 ) -> Result<(TokenStream, Vec<TokenStream>)> {
     let interface_name = format_ident!("{}", interface.name);
 
     let context = RenderContext {
         parent: TypeDef::Interface(interface),
+        config: render_config,
     };
 
     let mut interface_field_tokens = Vec::<TokenStream>::new();
+    let mut gated_field_tokens = Vec::<TokenStream>::new();
     let mut all_dependency_tokens = Vec::<TokenStream>::new();
 
     let render_context = RenderContext {
         parent: TypeDef::Interface(interface),
+        config: render_config,
     };
 
     for interface_field in interface.fields.iter() {
         let field_name = &interface_field.name.to_snake_case_with_underscores();
-        let field_type = value_type_def_token(&interface_field.typ, &schema, &render_context)?
-            .to_string()
-            .replace(" ", "");
+        let field_type = field_type_token(
+            &interface_field.typ,
+            interface_field.primitive_type.as_ref(),
+            schema,
+            &render_context,
+        )?
+        .to_string()
+        .replace(' ', "");
 
         let field_token = quote! {field(name = #field_name, ty = #field_type )};
-        interface_field_tokens.push(field_token);
+        // a gated field can't be an argument of the #[graphql(..)] of the others.
+        match cfg_predicate_token(&interface_field.features) {
+            Some(predicate) => {
+                gated_field_tokens.push(quote! { #[cfg_attr(#predicate, graphql(#field_token))] })
+            }
+            None => interface_field_tokens.push(field_token),
+        }
 
         let mut dependencies = dependency(&interface_field.typ, schema, &context)?;
         all_dependency_tokens.append(&mut dependencies);
@@ -133,11 +127,19 @@ fn interface_token(
 
     let interface_fields_token = separate_by_comma(interface_field_tokens);
     let interface_memer_tokens = separate_by_comma(interface_memer_tokens);
+    let visible_attribute =
+        graphql_attribute_token(interface.visible.iter().map(visible_token).collect());
+    let cfg = cfg_token(&interface.features);
+    let directive_attributes = directive_attributes_token(&interface.directives, render_config)?;
     let interface_def = quote! {
 
+        #cfg
         #[derive(Interface)]
         #[graphql(#interface_fields_token)]
+        #(#gated_field_tokens)*
+        #visible_attribute
         #[derive(Debug, Clone)]
+        #directive_attributes
         pub enum #interface_name{
             #interface_memer_tokens
         }
@@ -161,6 +163,12 @@ fn find_implment_types_by_interface_type(
             impl_types.push(each_obj.name.to_string());
         }
     }
+    for each_mapped in structured_schema.definitions.mapped_types.values() {
+        for interface_type in each_mapped.impl_interface_name.iter() {
+            let impl_types = result.entry(interface_type.to_string()).or_insert(vec![]);
+            impl_types.push(each_mapped.name.to_string());
+        }
+    }
     result
 }
 
@@ -169,7 +177,6 @@ fn convert_interface_member(
     schema: &StructuredSchema,
     render_context: &RenderContext,
 ) -> Result<InterfaceMember> {
-    let member_type_name = format_ident!("{}", member);
     let member_enum_name = format_ident!("{}", member.to_camel_case());
 
     //TODO(tacogips) this conversion of interface member type to ValueTypeDef might be a bit hack-y?
@@ -177,12 +184,60 @@ fn convert_interface_member(
         value_type_name: member.to_string(),
         is_nullable: false,
     });
+    // a mapped member is its rust type, imported with the `using` of the mapping.
+    let member_type_name = field_type_token(&member_type, None, schema, render_context)?;
     let dependencies = dependency(&member_type, schema, render_context)?;
 
-    let member = quote! { #member_enum_name (#member_type_name) };
+    let cfg = cfg_token(&member_features(member, schema, render_context));
+    let member = quote! { #cfg #member_enum_name (#member_type_name) };
 
     Ok(InterfaceMember {
         member,
         dependencies,
     })
 }
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    pub fn keep_mapped_implementors() {
+        let schema = r#"
+        type Query {
+          node(id: ID!): Node
+        }
+
+        interface Node {
+          id: ID!
+        }
+
+        type User implements Node {
+          id: ID!
+        }
+
+        type Post implements Node {
+          id: ID!
+        }
+        "#;
+        let config: RendererConfig = toml::from_str(
+            r#"
+            [[type_mapping]]
+            graphql_type = "Post"
+            rust_type = "blog::Post"
+            using = "use crate::blog"
+            "#,
+        )
+        .unwrap();
+        let structured_schema = parse_schema(schema, &config).unwrap();
+        let source = interfaces_source(&structured_schema, &config)
+            .unwrap()
+            .unwrap()
+            .replace(' ', "");
+
+        assert!(source.contains("usecrate::blog;"));
+        assert!(source.contains("Post(blog::Post)"));
+        assert!(source.contains("User(User)"));
+    }
+}
@@ -1,10 +1,14 @@
 mod argument;
 mod comment;
+mod config_template;
 mod datasource;
 mod dependencies;
+mod directives;
 mod enums;
+mod explain;
 mod fields;
 mod files;
+mod generator;
 mod input_fields;
 mod input_objects;
 mod interfaces;
@@ -20,50 +24,49 @@ mod utils;
 
 use super::parse;
 use super::parse::*;
-use crate::config::{Phase, RendererConfig};
-use anyhow::{anyhow, Result};
+use crate::config::{Phase, RendererConfig, USING_DATASOURCE};
+use crate::error::{Error, Result};
 use comment::*;
-use files::{fmt_file, pathbuf_to_str};
+pub use config_template::config_template;
+pub use explain::explain_field;
+pub(crate) use fields::ResolverType;
+use files::pathbuf_to_str;
+pub use generator::*;
 use linter::*;
+use proc_macro2::TokenStream;
 use quote::*;
-use std::fs::{self, OpenOptions};
-use std::io::{BufWriter, Write};
+pub(crate) use scalars::BackingType;
+use std::fs;
 use std::path::{Path, PathBuf};
 
 pub struct RenderContext<'a> {
     pub parent: parse::TypeDef<'a>,
+    pub config: &'a RendererConfig,
 }
 
 impl<'a> RenderContext<'a> {
-    pub fn parent_name(&self) -> String {
+    pub fn parent_name(&self) -> Result<String> {
         match self.parent {
-            parse::TypeDef::Object(obj) => format!("{}", obj.name_string()),
-            parse::TypeDef::Enum(obj) => format!("{}", obj.name_string()),
-            parse::TypeDef::InputObject(obj) => format!("{}", obj.name_string()),
-            parse::TypeDef::Union(obj) => format!("{}", obj.name_string()),
-            parse::TypeDef::Interface(obj) => format!("{}", obj.name_string()),
-            _ => panic!("invalid parent : {:?}", self.parent),
+            parse::TypeDef::Object(obj) => Ok(obj.name_string()),
+            parse::TypeDef::Enum(obj) => Ok(obj.name_string()),
+            parse::TypeDef::InputObject(obj) => Ok(obj.name_string()),
+            parse::TypeDef::Union(obj) => Ok(obj.name_string()),
+            parse::TypeDef::Interface(obj) => Ok(obj.name_string()),
+            parse::TypeDef::Directive(obj) => Ok(obj.name_string()),
+            _ => Err(Error::InvalidParent(self.parent.name())),
         }
     }
 }
 
-struct ModInfo {
-    objects_written: bool,
-    input_objects_written: bool,
-    union_written: bool,
-    scalar_written: bool,
-    interface_written: bool,
-    enum_written: bool,
-}
-
 pub fn output_datasource(
     output_dir: &str,
     structured_schema: StructuredSchema,
     config: &RendererConfig,
 ) -> Result<()> {
     setup_output_dir(output_dir)?;
-    datasource_mod_file(output_dir, &structured_schema, &config)?;
-    Ok(())
+    Generator::new(&structured_schema, config)
+        .datasource_files()?
+        .write_to(output_dir)
 }
 
 pub fn output_schema(
@@ -72,129 +75,54 @@ pub fn output_schema(
     config: RendererConfig,
 ) -> Result<()> {
     setup_output_dir(output_dir)?;
+    let files = Generator::new(&structured_schema, &config).schema_files()?;
 
-    let objects_written = if config.phases.is_empty() || config.phases.contains(&Phase::Objects) {
-        objects::write_objects(output_dir, &structured_schema, &config)?;
-        true
-    } else {
-        false
-    };
-
-    let input_objects_written =
-        if config.phases.is_empty() || config.phases.contains(&Phase::InputObjects) {
-            input_objects::write_input_objects(output_dir, &structured_schema)?;
-            true
-        } else {
-            false
-        };
-
-    let union_written = if config.phases.is_empty() || config.phases.contains(&Phase::Unions) {
-        unions::write_unions(output_dir, &structured_schema)?;
-        true
-    } else {
-        false
-    };
-
-    let scalar_written = if config.phases.is_empty() || config.phases.contains(&Phase::Scalars) {
-        scalars::write_scalars(output_dir, &structured_schema)?;
-        true
-    } else {
-        false
-    };
-
-    let interface_written =
-        if config.phases.is_empty() || config.phases.contains(&Phase::Interfaces) {
-            interfaces::write_interfaces(output_dir, &structured_schema, &config)?;
-            true
-        } else {
-            false
-        };
-
-    let enum_written = if config.phases.is_empty() || config.phases.contains(&Phase::Enums) {
-        enums::write_enums(output_dir, &structured_schema, &config)?;
-        true
-    } else {
-        false
-    };
-
-    let log = ModInfo {
-        objects_written,
-        input_objects_written,
-        union_written,
-        scalar_written,
-        interface_written,
-        enum_written,
-    };
-
-    schema_mod_file(output_dir, log, &structured_schema)?;
-
-    Ok(())
-}
-
-fn schema_mod_file(output_dir: &str, info: ModInfo, schema: &StructuredSchema) -> Result<()> {
-    let mut output_file = PathBuf::from(output_dir);
-    output_file.push("mod.rs");
-    if output_file.exists() {
-        fs::remove_file(&output_file)?;
-    }
-    let file_path_str = pathbuf_to_str(&output_file);
-
-    let dest_file = OpenOptions::new()
-        .write(true)
-        .create(true)
-        .open(output_file.as_path())
-        .expect(format!("failed to open file : {}", file_path_str).as_ref());
-    let mut dest_file = BufWriter::new(dest_file);
-
-    dest_file.write(SUPPRESS_LINT.as_bytes())?;
-    dest_file.write(FILE_HEADER_COMMENT.as_bytes())?;
-
-    if info.objects_written {
-        dest_file.write(
-            quote! { mod objects; pub use objects::*; }
-                .to_string()
-                .as_bytes(),
-        )?;
-    }
-
-    if info.input_objects_written {
-        dest_file.write(
-            quote! { mod input_objects; pub use input_objects::*; }
-                .to_string()
-                .as_bytes(),
-        )?;
-    }
-
-    if info.union_written {
-        dest_file.write(
-            quote! { mod unions; pub use unions::*; }
-                .to_string()
-                .as_bytes(),
-        )?;
-    }
-
-    if info.scalar_written {
-        dest_file.write(
-            quote! { mod scalars; pub use scalars::*; }
-                .to_string()
-                .as_bytes(),
-        )?;
+    // remove the outputs of the previous run that are no longer generated.
+    for phase in Phase::all() {
+        if !config.phase_enabled(&phase) || files.contains(phase.file_name()) {
+            continue;
+        }
+        let mut stale_file = PathBuf::from(output_dir);
+        stale_file.push(phase.file_name());
+        if stale_file.exists() {
+            fs::remove_file(&stale_file)
+                .map_err(|e| Error::file(pathbuf_to_str(&stale_file), e))?;
+        }
     }
 
-    if info.interface_written {
-        dest_file.write(
-            quote! { mod interfaces; pub use interfaces::*; }
-                .to_string()
-                .as_bytes(),
-        )?;
-    }
+    files.write_to(output_dir)
+}
 
-    if info.enum_written {
-        dest_file.write(
-            quote! { mod enums; pub use enums::*; }
-                .to_string()
-                .as_bytes(),
-        )?;
+fn schema_mod_source(
+    written_phases: &[(Phase, String)],
+    layout: &ModuleLayout,
+    schema: &StructuredSchema,
+) -> Result<String> {
+    let mut source = String::new();
+
+    source.push_str(SUPPRESS_LINT);
+    source.push_str(FILE_HEADER_COMMENT);
+
+    for (phase, phase_source) in written_phases {
+        let module = format_ident!("{}", phase.module_name());
+        let module_def = match layout {
+            ModuleLayout::Files => quote! { mod #module; },
+            ModuleLayout::Include(dir) => {
+                let path = pathbuf_to_str(&dir.join(phase.file_name()));
+                quote! { mod #module { include!(#path); } }
+            }
+            ModuleLayout::Inline => {
+                let phase_tokens =
+                    phase_source
+                        .parse::<TokenStream>()
+                        .map_err(|e| Error::Format {
+                            path: phase.file_name(),
+                            message: e.to_string(),
+                        })?;
+                quote! { mod #module { #phase_tokens } }
+            }
+        };
+        source.push_str(&quote! { #module_def pub use #module::*; }.to_string());
     }
 
     match schema.query_name.as_ref().map(|q| {
@@ -202,7 +130,7 @@ fn schema_mod_file(output_dir: &str, info: ModInfo, schema: &StructuredSchema) -
         quote! { #query }
     }) {
         Some(query_token) => {
-            dest_file.write(quote! { use async_graphql::*; }.to_string().as_bytes())?;
+            source.push_str(&quote! { use async_graphql::*; }.to_string());
             let mutation_token = schema
                 .mutation_name
                 .as_ref()
@@ -212,14 +140,52 @@ fn schema_mod_file(output_dir: &str, info: ModInfo, schema: &StructuredSchema) -
                 })
                 .unwrap_or_else(|| quote! {EmptyMutation});
 
+            // Typed IDs are all named `ID` in the schema.
+            let has_typed_ids = schema
+                .definitions
+                .scalars
+                .values()
+                .any(|scalar| scalar.id_of.is_some());
+            let build_token = if has_typed_ids {
+                quote! {
+                    Schema::build_with_ignore_name_conflicts(#query_token{},#mutation_token{}, EmptySubscription, ["ID"])
+                }
+            } else {
+                quote! {
+                    Schema::build(#query_token{},#mutation_token{}, EmptySubscription)
+                }
+            };
+
+            let directives_written = written_phases
+                .iter()
+                .any(|(phase, _)| *phase == Phase::Directives);
+            let body_token = if directives_written {
+                let registrations: Vec<TokenStream> = directives::sorted_directives(schema)
+                    .into_iter()
+                    .map(|directive| {
+                        let cfg = tokens::cfg_token(&directive.features);
+                        let factory_name = directives::directive_factory_name(directive);
+                        quote! {
+                            #cfg
+                            let builder = builder.directive(#factory_name);
+                        }
+                    })
+                    .collect();
+                quote! {
+                    let builder = #build_token;
+                    #(#registrations)*
+                    builder
+                }
+            } else {
+                build_token
+            };
             let schema_token = quote! {
                 pub fn schema_builder() -> SchemaBuilder<#query_token, #mutation_token, EmptySubscription> {
-                    Schema::build(#query_token{},#mutation_token{}, EmptySubscription)
+                    #body_token
                 }
             };
 
-            dest_file.write(schema_token.to_string().as_bytes())?;
-            dest_file.flush()?;
+            source.push_str(&schema_token.to_string());
         }
         None => {
             let schema_token = r#"
@@ -230,52 +196,40 @@ fn schema_mod_file(output_dir: &str, info: ModInfo, schema: &StructuredSchema) -
                 // }
             "#;
 
-            dest_file.write(schema_token.as_bytes())?;
-            dest_file.flush()?;
+            source.push_str(schema_token);
         }
     }
 
-    fmt_file(file_path_str)?;
-
-    Ok(())
+    Ok(source)
 }
 
 pub fn setup_output_dir(output_dir: &str) -> Result<()> {
     let output_path = Path::new(output_dir);
     if output_path.exists() {
-        let output_metadata = fs::metadata(output_dir)?;
+        let output_metadata = fs::metadata(output_dir).map_err(|e| Error::file(output_dir, e))?;
         if !output_metadata.is_dir() {
-            return Err(anyhow!("output path {} is not dir.", output_dir));
+            return Err(Error::NotADirectory(output_dir.to_string()));
         }
     } else {
-        fs::create_dir_all(output_dir)?;
+        fs::create_dir_all(output_dir).map_err(|e| Error::file(output_dir, e))?;
     }
     Ok(())
 }
 
-fn datasource_mod_file(
-    output_dir: &str,
+fn datasource_mod_source(
     schema: &StructuredSchema,
     render_config: &RendererConfig,
-) -> Result<()> {
-    let mut output_file = PathBuf::from(output_dir);
-    output_file.push("mod.rs");
-    if output_file.exists() {
-        fs::remove_file(&output_file)?;
-    }
-
-    let file_path_str = pathbuf_to_str(&output_file);
-    let dest_file = OpenOptions::new()
-        .write(true)
-        .create(true)
-        .open(output_file.as_path())
-        .expect(format!("failed to open file : {}", file_path_str).as_ref());
-    let mut dest_file = BufWriter::new(dest_file);
+) -> Result<String> {
+    let mut source = String::new();
 
     let header = quote! {
          use async_graphql::*;
     };
-    dest_file.write(header.to_string().as_bytes())?;
+    source.push_str(&header.to_string());
+    let dependencies_token = dependencies::dependency_strs_to_token(
+        dependencies::config_dependencies(render_config, USING_DATASOURCE)?,
+    );
+    source.push_str(&dependencies_token.to_string());
 
     let methods = datasource::empty_datasource_methods(schema, render_config)?;
     let methods = tokens::separate_by_space(methods);
@@ -288,11 +242,14 @@ fn datasource_mod_file(
         }
     };
 
-    dest_file.write(datasource.to_string().as_bytes())?;
+    source.push_str(&datasource.to_string());
 
-    dest_file.flush()?;
-
-    fmt_file(file_path_str)?;
+    let custom_directives = datasource::empty_custom_directives(schema);
+    if !custom_directives.is_empty() {
+        source.push_str(CUSTOM_DIRECTIVES_COMMENT);
+        let custom_directives = tokens::separate_by_space(custom_directives);
+        source.push_str(&custom_directives.to_string());
+    }
 
-    Ok(())
+    Ok(source)
 }
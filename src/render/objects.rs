@@ -2,32 +2,19 @@ use super::super::parse::*;
 use super::comment::*;
 use super::dependencies::*;
 use super::fields::*;
-use super::files::{fmt_file, pathbuf_to_str};
 use super::sorter::sort_by_line_pos_and_name;
 use super::tokens::*;
 use super::RenderContext;
 use crate::config::*;
-use anyhow::Result;
+use crate::error::{Result, ResultExt, Stage};
 use proc_macro2::TokenStream;
 use quote::*;
 use std::collections::{HashMap, HashSet};
-use std::fs::{self, OpenOptions};
-use std::io::{BufWriter, Write};
-use std::path::PathBuf;
 
-pub fn write_objects(
-    output_dir: &str,
+pub fn objects_source(
     structured_schema: &StructuredSchema,
     render_config: &RendererConfig,
-) -> Result<bool> {
-    let mut output_file = PathBuf::from(output_dir);
-    output_file.push("objects.rs");
-    let file_path_str = pathbuf_to_str(&output_file);
-
-    if output_file.exists() {
-        fs::remove_file(&file_path_str)?;
-    }
-
+) -> Result<Option<String>> {
     let mut objects: Vec<&Object> = structured_schema
         .definitions
         .objects
@@ -35,7 +22,7 @@ pub fn write_objects(
         .into_iter()
         .collect();
     if objects.is_empty() {
-        return Ok(false);
+        return Ok(None);
     }
     objects.sort_by(sort_by_line_pos_and_name);
 
@@ -56,7 +43,8 @@ pub fn write_objects(
             &custom_member_types,
             &additional_resolvers,
             &hidden_fields,
-        )?;
+        )
+        .in_element(Stage::Objects, &each_obj.name)?;
 
         object_defs.push(object_token.to_string());
 
@@ -65,34 +53,26 @@ pub fn write_objects(
         }
     }
 
-    let dest_file = OpenOptions::new()
-        .write(true)
-        .create(true)
-        .open(output_file.as_path())
-        .expect(format!("failed to open file : {}", file_path_str).as_ref());
-    let mut dest_file = BufWriter::new(dest_file);
-
-    let header = format!("{}", render_config.header)
-        .parse::<TokenStream>()
-        .unwrap();
+    let mut source = String::new();
+    let header = config_snippet_token(&render_config.header(), "header")?;
 
     let header = quote! { #header };
 
-    dest_file.write(FILE_HEADER_COMMENT.as_bytes())?;
-    dest_file.write(header.to_string().as_bytes())?;
+    source.push_str(FILE_HEADER_COMMENT);
+    source.push_str(&header.to_string());
 
+    let mut imports = config_dependencies(render_config, Phase::Objects.module_name())?;
     if !render_config.no_dependency_imports {
-        let dependencies_token = dependency_strs_to_token(all_dependencies);
-        dest_file.write(dependencies_token.to_string().as_bytes())?;
+        imports.extend(all_dependencies);
     }
+    let dependencies_token = dependency_strs_to_token(imports);
+    source.push_str(&dependencies_token.to_string());
 
     for each_obj_def in object_defs {
-        dest_file.write(each_obj_def.as_bytes())?;
+        source.push_str(&each_obj_def);
     }
 
-    dest_file.flush()?;
-    fmt_file(file_path_str)?;
-    Ok(true)
+    Ok(Some(source))
 }
 
 fn object_token(
@@ -112,6 +92,7 @@ fn object_token(
 
     let context = RenderContext {
         parent: TypeDef::Object(object),
+        config: render_config,
     };
 
     let field_resolver = resolver_setting.get(&object.name);
@@ -133,14 +114,14 @@ fn object_token(
         let mut bodies: Vec<TokenStream> = additional_resolvers
             .bodies
             .iter()
-            .map(|e| e.parse::<TokenStream>().unwrap())
-            .collect();
+            .map(|e| config_snippet_token(e, &additional_resolvers.entry_name))
+            .collect::<Result<Vec<TokenStream>>>()?;
 
         let mut usings: Vec<TokenStream> = additional_resolvers
             .using
             .iter()
-            .map(|e| e.parse::<TokenStream>().unwrap())
-            .collect();
+            .map(|e| config_snippet_token(e, &additional_resolvers.entry_name))
+            .collect::<Result<Vec<TokenStream>>>()?;
 
         methods.append(&mut bodies);
         dependencies.append(&mut usings);
@@ -150,31 +131,47 @@ fn object_token(
         let mut defs: Vec<TokenStream> = hidden_fields
             .field_defs
             .iter()
-            .map(|e| e.parse::<TokenStream>().unwrap())
-            .collect();
+            .map(|e| config_snippet_token(e, &hidden_fields.entry_name))
+            .collect::<Result<Vec<TokenStream>>>()?;
 
         let mut usings: Vec<TokenStream> = hidden_fields
             .using
             .iter()
-            .map(|e| e.parse::<TokenStream>().unwrap())
-            .collect();
+            .map(|e| config_snippet_token(e, &hidden_fields.entry_name))
+            .collect::<Result<Vec<TokenStream>>>()?;
 
         members.append(&mut defs);
         dependencies.append(&mut usings);
     }
 
     let additional_attributes = match &render_config.additional_attributes {
-        Some(attributes) => format!("{},", attributes).parse::<TokenStream>().unwrap(),
+        Some(attributes) => {
+            config_snippet_token(&format!("{},", attributes), "additional_attributes")?
+        }
         None => TokenStream::new(),
     };
 
     let members = separate_by_comma(members);
     let methods = separate_by_space(methods);
 
+    let mut object_arguments: Vec<TokenStream> = object.visible.iter().map(visible_token).collect();
+    if let Some(cache_control) = &object.cache_control {
+        object_arguments.push(cache_control_token(cache_control));
+    }
+    let object_attribute = if object_arguments.is_empty() {
+        quote! { #[Object] }
+    } else {
+        let object_arguments = separate_by_comma(object_arguments);
+        quote! { #[Object(#object_arguments)] }
+    };
+
+    let cfg = cfg_token(&object.features);
+    let directive_attributes = directive_attributes_token(&object.directives, render_config)?;
     let methods = match render_config.no_object_impl {
         true => quote! {},
         false => quote! {
-            #[Object]
+            #cfg
+            #object_attribute
             impl #object_name {
                 #methods
             }
@@ -184,7 +181,9 @@ fn object_token(
     let object_def = quote! {
         #comment
 
+        #cfg
         #[derive(#additional_attributes Debug, Clone)]
+        #directive_attributes
         pub struct #object_name{
             #members
         }
@@ -194,3 +193,47 @@ fn object_token(
     };
     Ok((object_def, dependencies))
 }
+
+#[cfg(test)]
+mod test {
+
+    use super::super::{attribute, render_for_test, ParsedSource};
+
+    #[test]
+    pub fn render_cache_control() {
+        let schema = r#"
+        schema {
+          query: Query
+        }
+
+        type Query {
+          me: User! @cacheControl(maxAge: 30)
+        }
+
+        type User @cacheControl(maxAge: 60, scope: PRIVATE) {
+          name: String!
+          email: String! @cacheControl(scope: PRIVATE)
+        }
+        "#;
+        let (files, _) = render_for_test(schema, "").unwrap();
+
+        let objects = ParsedSource::of(&files, "objects.rs");
+        assert_eq!(
+            objects.method("Query", "me").attrs,
+            vec![attribute("#[graphql(cache_control(max_age = 30))]")]
+        );
+        assert_eq!(
+            objects.impls("Query")[0].attrs,
+            vec![attribute("#[Object]")]
+        );
+        assert_eq!(
+            objects.impls("User")[0].attrs,
+            vec![attribute("#[Object(cache_control(max_age = 60, private))]")]
+        );
+        assert_eq!(
+            objects.method("User", "email").attrs,
+            vec![attribute("#[graphql(cache_control(private))]")]
+        );
+        assert!(objects.method("User", "name").attrs.is_empty());
+    }
+}
@@ -1,23 +1,22 @@
 use super::super::parse::*;
 use super::comment::*;
-use super::files::{fmt_file, pathbuf_to_str};
+use super::dependencies::*;
 use super::sorter::sort_by_line_pos_and_name;
-use anyhow::Result;
+use super::tokens::{
+    cfg_token, config_snippet_token, directive_attributes_token, separate_by_comma, visible_token,
+};
+use crate::config::{Phase, PrimitiveSetting, RendererConfig, ScalarSetting};
+use crate::error::{Error, Result, ResultExt, Stage};
 use proc_macro2::TokenStream;
 use quote::*;
-use std::fs::{self, OpenOptions};
-use std::io::{BufWriter, Write};
-use std::path::PathBuf;
-
-pub fn write_scalars(output_dir: &str, structured_schema: &StructuredSchema) -> Result<bool> {
-    let mut output_file = PathBuf::from(output_dir);
-    output_file.push("scalars.rs");
-    let file_path_str = pathbuf_to_str(&output_file);
-
-    if output_file.exists() {
-        fs::remove_file(&file_path_str)?;
-    }
+use regex::Regex;
+use std::str::FromStr;
+use strum::{AsRefStr, EnumString};
 
+pub fn scalars_source(
+    structured_schema: &StructuredSchema,
+    config: &RendererConfig,
+) -> Result<Option<String>> {
     let mut scalars: Vec<&Scalar> = structured_schema
         .definitions
         .scalars
@@ -25,57 +24,241 @@ pub fn write_scalars(output_dir: &str, structured_schema: &StructuredSchema) ->
         .into_iter()
         .collect();
     if scalars.is_empty() {
-        return Ok(false);
+        return Ok(None);
     }
     scalars.sort_by(sort_by_line_pos_and_name);
 
     let mut scalar_defs = Vec::<String>::new();
+    let scalar_settings = config.scalar_settings();
 
     for each_scalar in scalars {
-        let scalar_token = scalar_token(each_scalar, &structured_schema)?;
+        let scalar_token = match &each_scalar.id_of {
+            Some(_) => typed_id_token(each_scalar, config),
+            None => scalar_token(each_scalar, scalar_settings.get(&each_scalar.name), config),
+        }
+        .in_element(Stage::Scalars, &each_scalar.name)?;
         scalar_defs.push(scalar_token.to_string());
     }
 
-    let dest_file = OpenOptions::new()
-        .write(true)
-        .create(true)
-        .open(output_file.as_path())
-        .expect(format!("failed to open file : {}", file_path_str).as_ref());
-    let mut dest_file = BufWriter::new(dest_file);
-
-    dest_file.write(FILE_HEADER_COMMENT.as_bytes())?;
+    let mut source = String::new();
+    source.push_str(FILE_HEADER_COMMENT);
     let header = quote! {
         use async_graphql::*;
     };
 
-    dest_file.write(header.to_string().as_bytes())?;
+    source.push_str(&header.to_string());
+    let dependencies_token =
+        dependency_strs_to_token(config_dependencies(config, Phase::Scalars.module_name())?);
+    source.push_str(&dependencies_token.to_string());
 
     for each_obj_def in scalar_defs {
-        dest_file.write(each_obj_def.as_bytes())?;
+        source.push_str(&each_obj_def);
+    }
+
+    Ok(Some(source))
+}
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy, EnumString, AsRefStr)]
+pub enum BackingType {
+    #[strum(serialize = "String")]
+    Str,
+    #[strum(serialize = "i64")]
+    I64,
+    #[strum(serialize = "f64")]
+    F64,
+    #[strum(serialize = "bool")]
+    Bool,
+    #[strum(serialize = "serde_json::Value")]
+    Json,
+}
+
+impl BackingType {
+    fn is_numeric(&self) -> bool {
+        *self == BackingType::I64 || *self == BackingType::F64
+    }
+}
+
+fn backing_type(setting: Option<&ScalarSetting>) -> Result<BackingType> {
+    match setting {
+        Some(ScalarSetting {
+            backing_type: Some(backing_type),
+            ..
+        }) => BackingType::from_str(backing_type).map_err(|_| {
+            Error::config_entry(
+                setting.unwrap().entry_name(),
+                format!(
+                    "invalid backing_type \"{}\", expected one of String, i64, f64, bool or serde_json::Value",
+                    backing_type
+                ),
+            )
+        }),
+        _ => Ok(BackingType::Str),
+    }
+}
+
+/// The checks of `pattern`, `min` and `max` on the parsed value `v`.
+fn value_checks_token(setting: &ScalarSetting, backing_type: BackingType) -> Result<TokenStream> {
+    let entry = setting.entry_name();
+    let mut checks = Vec::<TokenStream>::new();
+
+    if let Some(pattern) = &setting.pattern {
+        if backing_type != BackingType::Str {
+            return Err(Error::config_entry(
+                entry,
+                "pattern needs the String backing_type",
+            ));
+        }
+        Regex::new(pattern).map_err(|e| Error::config_entry(&entry, e))?;
+        // std::sync::OnceLock needs rust 1.70 in the generated crate.
+        checks.push(quote! {
+            static PATTERN: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+            let pattern = PATTERN.get_or_init(|| regex::Regex::new(#pattern).expect("checked on generation"));
+            if !pattern.is_match(&v) {
+                return Err(InputValueError::custom(format!("{} does not match {}", v, #pattern)));
+            }
+        });
+    }
+
+    for (bound, is_min) in [(setting.min, true), (setting.max, false)].iter() {
+        let bound = match bound {
+            Some(bound) => *bound,
+            None => continue,
+        };
+        if !backing_type.is_numeric() {
+            return Err(Error::config_entry(
+                entry,
+                "min and max need the i64 or f64 backing_type",
+            ));
+        }
+        let bound_token = if backing_type == BackingType::I64 {
+            if bound.fract() != 0.0 {
+                return Err(Error::config_entry(
+                    entry,
+                    format!("{} is not an integer", bound),
+                ));
+            }
+            let bound = bound as i64;
+            quote! { #bound }
+        } else {
+            quote! { #bound }
+        };
+        checks.push(if *is_min {
+            quote! {
+                if v < #bound_token {
+                    return Err(InputValueError::custom(format!("{} is less than {}", v, #bound_token)));
+                }
+            }
+        } else {
+            quote! {
+                if v > #bound_token {
+                    return Err(InputValueError::custom(format!("{} is greater than {}", v, #bound_token)));
+                }
+            }
+        });
     }
 
-    dest_file.flush()?;
-    fmt_file(file_path_str)?;
-    Ok(true)
+    Ok(quote! { #(#checks)* })
 }
 
-fn scalar_token(scalar: &Scalar, _schema: &StructuredSchema) -> Result<TokenStream> {
+fn scalar_token(
+    scalar: &Scalar,
+    setting: Option<&ScalarSetting>,
+    config: &RendererConfig,
+) -> Result<TokenStream> {
     let scalar_name = format_ident!("{}", scalar.name);
-    let scalar_def = quote! {
+    let backing_type = backing_type(setting)?;
+    let backing_type_token = config_snippet_token(backing_type.as_ref(), "backing_type")?;
 
-    #[derive(Debug, Clone)]
-    pub struct #scalar_name(pub String);
-    #[Scalar]
-    impl ScalarType for #scalar_name {
-        fn parse(value: Value) -> InputValueResult<Self> {
+    let checks = match setting {
+        Some(setting) => value_checks_token(setting, backing_type)?,
+        None => quote! {},
+    };
+
+    let parse_body = if backing_type == BackingType::Json {
+        quote! {
+            let v = value.into_json().map_err(InputValueError::custom)?;
+            #checks
+            Ok(#scalar_name(v))
+        }
+    } else {
+        let (value_pattern, value_extract) = match backing_type {
+            BackingType::Str => (quote! { Value::String(s) }, quote! { s }),
+            BackingType::I64 => (
+                quote! { Value::Number(n) if n.is_i64() },
+                quote! { n.as_i64().unwrap() },
+            ),
+            BackingType::F64 => (
+                quote! { Value::Number(n) if n.as_f64().is_some() },
+                quote! { n.as_f64().unwrap() },
+            ),
+            BackingType::Bool => (quote! { Value::Boolean(b) }, quote! { b }),
+            BackingType::Json => unreachable!(),
+        };
+        let value_arm = if checks.is_empty() {
+            quote! { Ok( #scalar_name(#value_extract)) }
+        } else {
+            quote! {
+                {
+                    let v = #value_extract;
+                    #checks
+                    Ok(#scalar_name(v))
+                }
+            }
+        };
+        quote! {
             match value {
-                Value::String(s) => Ok( #scalar_name(s)),
+                #value_pattern => #value_arm,
                 _ => Err(InputValueError::expected_type(value)),
 
             }
         }
+    };
+
+    let to_value = match backing_type {
+        BackingType::Str => quote! { Value::String(self.0.to_string()) },
+        BackingType::I64 => quote! { Value::Number(self.0.into()) },
+        BackingType::F64 => {
+            quote! { Number::from_f64(self.0).map(Value::Number).unwrap_or(Value::Null) }
+        }
+        BackingType::Bool => quote! { Value::Boolean(self.0) },
+        BackingType::Json => quote! { Value::from_json(self.0.clone()).unwrap_or(Value::Null) },
+    };
+
+    let derives = match setting.and_then(|setting| setting.derives.as_ref()) {
+        Some(derives) => config_snippet_token(derives, &setting.unwrap().entry_name())?,
+        None => quote! {},
+    };
+
+    let specified_by_url = setting
+        .and_then(|setting| setting.specified_by_url.as_ref())
+        .or(scalar.specified_by_url.as_ref());
+    let mut scalar_arguments: Vec<TokenStream> = scalar.visible.iter().map(visible_token).collect();
+    if let Some(url) = specified_by_url {
+        scalar_arguments.push(quote! { specified_by_url = #url });
+    }
+    let scalar_attribute = if scalar_arguments.is_empty() {
+        quote! { #[Scalar] }
+    } else {
+        let scalar_arguments = separate_by_comma(scalar_arguments);
+        quote! { #[Scalar(#scalar_arguments)] }
+    };
+
+    let cfg = cfg_token(&scalar.features);
+    let directive_attributes = directive_attributes_token(&scalar.directives, config)?;
+    let scalar_def = quote! {
+
+    #cfg
+    #[derive(Debug, Clone, #derives)]
+    #directive_attributes
+    pub struct #scalar_name(pub #backing_type_token);
+    #cfg
+    #scalar_attribute
+    impl ScalarType for #scalar_name {
+        fn parse(value: Value) -> InputValueResult<Self> {
+            #parse_body
+        }
         fn to_value(&self) -> Value {
-            Value::String(self.0.to_string())
+            #to_value
         }
     }
 
@@ -83,3 +266,203 @@ fn scalar_token(scalar: &Scalar, _schema: &StructuredSchema) -> Result<TokenStre
     };
     Ok(scalar_def)
 }
+
+/// The newtype of a typed ID. It is parsed and output as the ID it wraps and named `ID` in the schema.
+fn typed_id_token(scalar: &Scalar, config: &RendererConfig) -> Result<TokenStream> {
+    let scalar_name = format_ident!("{}", scalar.name);
+    let id_type = config_snippet_token(
+        &config.primitive_type(&PrimitiveKind::ID),
+        &PrimitiveSetting::entry_name(&PrimitiveKind::ID),
+    )?;
+
+    let cfg = cfg_token(&scalar.features);
+    let scalar_def = quote! {
+
+    #cfg
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    pub struct #scalar_name(pub #id_type);
+    #cfg
+    #[Scalar(name = "ID")]
+    impl ScalarType for #scalar_name {
+        fn parse(value: Value) -> InputValueResult<Self> {
+            <#id_type as ScalarType>::parse(value).map(#scalar_name).map_err(InputValueError::propagate)
+        }
+        fn to_value(&self) -> Value {
+            ScalarType::to_value(&self.0)
+        }
+    }
+
+
+    };
+    Ok(scalar_def)
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::super::{argument, attribute, render_for_test, syntax, ParsedSource};
+
+    #[test]
+    pub fn map_scalars_with_presets() {
+        let schema = r#"
+        scalar DateTime
+        scalar UUID
+        scalar Email
+
+        type Query {
+          createdAt: DateTime!
+          id: UUID!
+          email: Email!
+        }
+        "#;
+        let (files, _) = render_for_test(
+            schema,
+            r#"
+            scalar_presets = ["chrono", "uuid"]
+
+            [[type_mapping]]
+            graphql_type = "UUID"
+            rust_type = "crate::Id"
+            "#,
+        )
+        .unwrap();
+
+        let objects = ParsedSource::of(&files, "objects.rs");
+        assert_eq!(
+            objects.field("Query", "created_at").ty,
+            syntax("chrono::DateTime<chrono::Utc>")
+        );
+        assert_eq!(objects.field("Query", "id").ty, syntax("crate::Id"));
+        assert_eq!(objects.field("Query", "email").ty, syntax("Email"));
+        let scalars = ParsedSource::of(&files, "scalars.rs");
+        assert_eq!(scalars.struct_names(), vec!["Email"]);
+    }
+
+    #[test]
+    pub fn render_scalar_settings() {
+        let schema = r#"
+        scalar Email @specifiedBy(url: "https://example.com/email")
+        scalar Count
+        scalar Payload
+
+        type Query {
+          email: Email!
+          count: Count!
+          payload: Payload!
+        }
+        "#;
+        let (files, _) = render_for_test(
+            schema,
+            r#"
+            [[scalar]]
+            target_scalar = "Email"
+            pattern = "^[^@]+@[^@]+$"
+            derives = "PartialEq, Eq, Hash"
+
+            [[scalar]]
+            target_scalar = "Count"
+            backing_type = "i64"
+            min = 0
+
+            [[scalar]]
+            target_scalar = "Payload"
+            backing_type = "serde_json::Value"
+            "#,
+        )
+        .unwrap();
+
+        // the checks of the values are run by the scalars test of schema_macro.
+        let scalars = ParsedSource::of(&files, "scalars.rs");
+        assert_eq!(
+            *scalars.structure("Email"),
+            syntax("#[derive(Debug, Clone, PartialEq, Eq, Hash)] pub struct Email(pub String);")
+        );
+        assert_eq!(
+            *scalars.structure("Count"),
+            syntax("#[derive(Debug, Clone)] pub struct Count(pub i64);")
+        );
+        assert_eq!(
+            *scalars.structure("Payload"),
+            syntax("#[derive(Debug, Clone)] pub struct Payload(pub serde_json::Value);")
+        );
+
+        let result = render_for_test(
+            schema,
+            r#"
+            [[scalar]]
+            target_scalar = "Count"
+            backing_type = "bool"
+            min = 0
+            "#,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn render_typed_ids() {
+        let schema = r#"
+        schema {
+          query: Query
+        }
+
+        type Query {
+          user(id: ID!): User
+          orders(userId: ID!, productIds: [ID!]): [Order!]!
+        }
+
+        type User {
+          id: ID!
+        }
+
+        type Order {
+          id: ID!
+          buyer: ID!
+        }
+
+        type Product {
+          id: ID!
+        }
+        "#;
+        let (files, _) = render_for_test(
+            schema,
+            r#"
+            typed_ids = true
+
+            [[field]]
+            target_type = "Order"
+            target_field = "buyer"
+            id_of = "User"
+            "#,
+        )
+        .unwrap();
+
+        let objects = ParsedSource::of(&files, "objects.rs");
+        let query_user = &objects.method("Query", "user").sig;
+        assert_eq!(*argument(query_user, "id").ty, syntax("UserId"));
+        let query_orders = &objects.method("Query", "orders").sig;
+        assert_eq!(*argument(query_orders, "user_id").ty, syntax("UserId"));
+        assert_eq!(
+            *argument(query_orders, "product_ids").ty,
+            syntax("Option<Vec<ProductId>>")
+        );
+        assert_eq!(objects.field("Order", "id").ty, syntax("OrderId"));
+        assert_eq!(objects.field("Order", "buyer").ty, syntax("UserId"));
+
+        let scalars = ParsedSource::of(&files, "scalars.rs");
+        assert_eq!(
+            *scalars.structure("UserId"),
+            syntax("#[derive(Debug, Clone, PartialEq, Eq, Hash)] pub struct UserId(pub ID);")
+        );
+        assert_eq!(
+            scalars.impls("UserId")[0].attrs,
+            vec![attribute("#[Scalar(name = \"ID\")]")]
+        );
+        let mod_source = ParsedSource::of(&files, "mod.rs");
+        assert_eq!(
+            *mod_source.function("schema_builder").block,
+            syntax(
+                "{ Schema::build_with_ignore_name_conflicts(Query {}, EmptyMutation {}, EmptySubscription, [\"ID\"]) }"
+            )
+        );
+    }
+}
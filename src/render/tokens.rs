@@ -1,5 +1,203 @@
-use proc_macro2::TokenStream;
+use crate::config::{DirectiveMapping, RendererConfig, Visible};
+use crate::error::{Error, Result};
+use crate::parse::{CacheControl, Directive};
+use proc_macro2::{Delimiter, Literal, TokenStream, TokenTree};
 use quote::*;
+use syn::parse::Parser;
+use syn::Attribute;
+
+/// Tokenize a rust snippet written in the config. `entry` names the config entry the snippet came from.
+pub fn config_snippet_token(snippet: &str, entry: &str) -> Result<TokenStream> {
+    snippet
+        .parse::<TokenStream>()
+        .map_err(|e| Error::config_entry(entry, format!("invalid rust code `{}`: {}", snippet, e)))
+}
+
+/// `visible = false` or `visible = "fn_name"` of an async-graphql attribute.
+pub fn visible_token(visible: &Visible) -> TokenStream {
+    match visible {
+        Visible::Bool(visible) => quote! { visible = #visible },
+        Visible::Function(function) => quote! { visible = #function },
+    }
+}
+
+/// `cache_control(max_age = 60, private)` of an async-graphql attribute.
+pub fn cache_control_token(cache_control: &CacheControl) -> TokenStream {
+    let mut arguments: Vec<TokenStream> = vec![];
+    if let Some(max_age) = cache_control.max_age {
+        let max_age = Literal::u64_unsuffixed(max_age);
+        arguments.push(quote! { max_age = #max_age });
+    }
+    if cache_control.private {
+        arguments.push(quote! { private });
+    }
+    let arguments = separate_by_comma(arguments);
+    quote! { cache_control(#arguments) }
+}
+
+/// `#[graphql(..)]` with the arguments, or nothing without them.
+pub fn graphql_attribute_token(arguments: Vec<TokenStream>) -> TokenStream {
+    if arguments.is_empty() {
+        quote! {}
+    } else {
+        let arguments = separate_by_comma(arguments);
+        quote! { #[graphql(#arguments)] }
+    }
+}
+
+/// `attributes` with `arguments` and the arguments of their `#[graphql(..)]` merged into one
+/// `#[graphql(..)]`, since async-graphql reads only the first one on a resolver method.
+/// `element` names the field in the error of an argument set both ways.
+pub fn merge_graphql_attributes(
+    attributes: TokenStream,
+    arguments: Vec<TokenStream>,
+    element: &str,
+) -> Result<TokenStream> {
+    let parsed = match Parser::parse2(Attribute::parse_outer, attributes.clone()) {
+        Ok(parsed) => parsed,
+        // not attributes, reported by the snippet check of the config
+        Err(_) => {
+            let graphql_attribute = graphql_attribute_token(arguments);
+            return Ok(quote! { #attributes #graphql_attribute });
+        }
+    };
+    let generated_names: Vec<String> = arguments.iter().filter_map(argument_name).collect();
+
+    let mut others = Vec::<TokenStream>::new();
+    let mut merged_arguments = Vec::<TokenStream>::new();
+    for attribute in parsed {
+        let attribute_arguments = match graphql_arguments(&attribute) {
+            Some(attribute_arguments) => attribute_arguments,
+            None => {
+                others.push(attribute.to_token_stream());
+                continue;
+            }
+        };
+        for argument in attribute_arguments {
+            if let Some(name) = argument_name(&argument) {
+                if generated_names.contains(&name) {
+                    return Err(Error::config_entry(
+                        format!("attribute of {}", element),
+                        format!(
+                            "{} of #[graphql(..)] is also generated from the schema or the config",
+                            name
+                        ),
+                    ));
+                }
+            }
+            merged_arguments.push(argument);
+        }
+    }
+    merged_arguments.extend(arguments);
+    let graphql_attribute = graphql_attribute_token(merged_arguments);
+    Ok(quote! { #(#others)* #graphql_attribute })
+}
+
+/// The comma separated arguments of `#[graphql(..)]`, None for the other attributes.
+fn graphql_arguments(attribute: &Attribute) -> Option<Vec<TokenStream>> {
+    if !attribute.path.is_ident("graphql") {
+        return None;
+    }
+    let mut tokens = attribute.tokens.clone().into_iter();
+    let group = match (tokens.next(), tokens.next()) {
+        (Some(TokenTree::Group(group)), None) if group.delimiter() == Delimiter::Parenthesis => {
+            group
+        }
+        _ => return None,
+    };
+    let mut arguments = vec![];
+    let mut argument = TokenStream::new();
+    for token in group.stream() {
+        match &token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' => {
+                arguments.push(std::mem::take(&mut argument));
+            }
+            _ => argument.extend(std::iter::once(token)),
+        }
+    }
+    arguments.push(argument);
+    Some(
+        arguments
+            .into_iter()
+            .filter(|argument| !argument.is_empty())
+            .collect(),
+    )
+}
+
+/// `visible` of `visible = false` or `cache_control` of `cache_control(..)`.
+fn argument_name(argument: &TokenStream) -> Option<String> {
+    match argument.clone().into_iter().next() {
+        Some(TokenTree::Ident(ident)) => Some(ident.to_string()),
+        _ => None,
+    }
+}
+
+/// `feature = "a"`, or `all(feature = "a", feature = "b")` for several features.
+pub fn cfg_predicate_token(features: &[String]) -> Option<TokenStream> {
+    let predicates: Vec<TokenStream> = features
+        .iter()
+        .map(|feature| quote! { feature = #feature })
+        .collect();
+    match predicates.len() {
+        0 => None,
+        1 => Some(predicates[0].clone()),
+        _ => {
+            let predicates = separate_by_comma(predicates);
+            Some(quote! { all(#predicates) })
+        }
+    }
+}
+
+/// `#[cfg(..)]` of the features, or nothing without them.
+pub fn cfg_token(features: &[String]) -> TokenStream {
+    match cfg_predicate_token(features) {
+        Some(predicate) => quote! { #[cfg(#predicate)] },
+        None => quote! {},
+    }
+}
+
+/// The `[[directive_mapping]] attribute` of the directives applied to an element.
+pub fn directive_attributes_token(
+    directives: &[Directive],
+    config: &RendererConfig,
+) -> Result<TokenStream> {
+    mapped_attributes_token(directives, config, |mapping| mapping.attribute.as_ref())
+}
+
+/// The `[[directive_mapping]] datasource_attribute` of the directives applied to a field.
+pub fn datasource_directive_attributes_token(
+    directives: &[Directive],
+    config: &RendererConfig,
+) -> Result<TokenStream> {
+    mapped_attributes_token(directives, config, |mapping| {
+        mapping.datasource_attribute.as_ref()
+    })
+}
+
+fn mapped_attributes_token<F>(
+    directives: &[Directive],
+    config: &RendererConfig,
+    template_of: F,
+) -> Result<TokenStream>
+where
+    F: Fn(&DirectiveMapping) -> Option<&String>,
+{
+    let mut attributes = Vec::<TokenStream>::new();
+    for directive in directives.iter() {
+        for mapping in config
+            .directive_mapping
+            .iter()
+            .flatten()
+            .filter(|mapping| mapping.directive == directive.name)
+        {
+            if let Some(template) = template_of(mapping) {
+                let attribute = mapping.interpolate(template, directive)?;
+                attributes.push(config_snippet_token(&attribute, &mapping.entry_name())?);
+            }
+        }
+    }
+    Ok(separate_by_space(attributes))
+}
 
 //TODO(tacogips) rename to  join_with_space
 pub fn separate_by_space(tokens: Vec<TokenStream>) -> TokenStream {
@@ -37,3 +235,267 @@ pub fn merge_with_trailing_semicomman(mut tokens: Vec<TokenStream>) -> TokenStre
         result
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::super::{argument, attribute, render_for_test, syntax, ParsedSource};
+
+    use super::*;
+
+    #[test]
+    pub fn merge_into_one_graphql_attribute() {
+        let attributes = quote! { #[graphql(name = "hello", guard = "Admin")] #[allow(unused)] };
+        let merged =
+            merge_graphql_attributes(attributes, vec![quote! { visible = false }], "Query.me")
+                .unwrap();
+        assert_eq!(
+            merged.to_string(),
+            quote! { #[allow(unused)] #[graphql(name = "hello", guard = "Admin", visible = false)] }
+                .to_string()
+        );
+
+        let error = merge_graphql_attributes(
+            quote! { #[graphql(visible = "is_admin")] },
+            vec![quote! { visible = false }],
+            "Query.me",
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid config entry attribute of Query.me: visible of #[graphql(..)] is also generated from the schema or the config"
+        );
+    }
+
+    #[test]
+    pub fn render_visibility_and_secrets() {
+        let schema = r#"
+        schema {
+          query: Query
+        }
+
+        type Query {
+          me: User!
+          login(name: String!, password: String! @sensitive): User!
+          audit(token: String!): String! @internal
+        }
+
+        type User {
+          name: String!
+          email: String!
+        }
+
+        input SignUpInput {
+          name: String!
+          password: String!
+        }
+
+        enum Role {
+          ADMIN @internal
+          MEMBER
+        }
+
+        type Debug @internal {
+          name: String!
+        }
+        "#;
+        let (files, datasource_files) = render_for_test(
+            schema,
+            r#"
+            [[type]]
+            target_type = "Debug"
+            visible = "crate::auth::is_admin"
+
+            [[field]]
+            target_type = "User"
+            target_field = "email"
+            visible = false
+
+            [[field]]
+            target_type = "SignUpInput"
+            target_field = "password"
+            secret = true
+
+            [[field]]
+            target_type = "Query"
+            target_field = "audit"
+            secret_arguments = ["token"]
+            "#,
+        )
+        .unwrap();
+
+        let secret = attribute("#[graphql(secret)]");
+        let hidden = attribute("#[graphql(visible = false)]");
+        let objects = ParsedSource::of(&files, "objects.rs");
+        assert_eq!(
+            objects.impls("Debug")[0].attrs,
+            vec![attribute("#[Object(visible = \"crate::auth::is_admin\")]")]
+        );
+        let login = &objects.method("Query", "login").sig;
+        assert!(argument(login, "name").attrs.is_empty());
+        assert_eq!(argument(login, "password").attrs, vec![secret.clone()]);
+        let audit = objects.method("Query", "audit");
+        assert_eq!(audit.attrs, vec![hidden.clone()]);
+        assert_eq!(argument(&audit.sig, "token").attrs, vec![secret.clone()]);
+        assert_eq!(objects.method("User", "email").attrs, vec![hidden.clone()]);
+        assert!(objects.method("User", "name").attrs.is_empty());
+        let input_objects = ParsedSource::of(&files, "input_objects.rs");
+        assert_eq!(
+            input_objects.field("SignUpInput", "password").attrs,
+            vec![secret]
+        );
+        assert!(input_objects.field("SignUpInput", "name").attrs.is_empty());
+        let enums = ParsedSource::of(&files, "enums.rs");
+        assert_eq!(enums.variant("Role", "Admin").attrs, vec![hidden]);
+        assert!(enums.variant("Role", "Member").attrs.is_empty());
+
+        let datasource = ParsedSource::of(&datasource_files, "mod.rs");
+        let query_login = &datasource.method("DataSource", "query_login").sig;
+        assert!(argument(query_login, "_password").attrs.is_empty());
+    }
+
+    #[test]
+    pub fn render_feature_gated_types() {
+        let schema = r#"
+        schema {
+          query: Query
+        }
+
+        type Query {
+          me: User!
+          search(filter: InvoiceFilter): [Entry!]!
+        }
+
+        type User {
+          name: String!
+          invoices: [Invoice!]!
+        }
+
+        type Invoice {
+          amount: Float!
+        }
+
+        input InvoiceFilter {
+          min: Float
+        }
+
+        union Entry = User | Invoice
+        "#;
+        let (files, datasource_files) = render_for_test(
+            schema,
+            r#"
+            [[type]]
+            target_type = "Invoice"
+            feature = "billing"
+
+            [[type]]
+            target_type = "InvoiceFilter"
+            feature = "billing"
+            "#,
+        )
+        .unwrap();
+
+        let cfg = attribute("#[cfg(feature = \"billing\")]");
+        let gated = |attrs: &[syn::Attribute]| attrs.contains(&cfg);
+        let objects = ParsedSource::of(&files, "objects.rs");
+        assert!(gated(&objects.structure("Invoice").attrs));
+        assert!(gated(&objects.impls("Invoice")[0].attrs));
+        assert!(gated(&objects.method("User", "invoices").attrs));
+        assert!(gated(&objects.method("Query", "search").attrs));
+        assert!(!gated(&objects.method("User", "name").attrs));
+        assert!(!gated(&objects.structure("User").attrs));
+        let filter_use: syn::ItemUse = syntax("use super::input_objects::InvoiceFilter;");
+        let filter_use = objects
+            .uses()
+            .into_iter()
+            .find(|item| item.tree == filter_use.tree)
+            .unwrap();
+        assert!(gated(&filter_use.attrs));
+        let unions = ParsedSource::of(&files, "unions.rs");
+        assert!(gated(&unions.variant("Entry", "Invoice").attrs));
+        assert!(!gated(&unions.variant("Entry", "User").attrs));
+        let input_objects = ParsedSource::of(&files, "input_objects.rs");
+        assert!(gated(&input_objects.structure("InvoiceFilter").attrs));
+
+        let datasource = ParsedSource::of(&datasource_files, "mod.rs");
+        assert!(gated(
+            &datasource.method("DataSource", "user_invoices").attrs
+        ));
+        assert!(!gated(&datasource.method("DataSource", "query_me").attrs));
+    }
+
+    #[test]
+    pub fn render_directive_mappings() {
+        let schema = r#"
+        schema {
+          query: Query
+        }
+
+        type Query @audit(level: "high") {
+          items(first: Int @audit(level: "low")): [Item!]! @rateLimit(max: 10, window: "1m")
+        }
+
+        type Item {
+          name: String! @audit(level: "low")
+        }
+
+        enum Kind {
+          BOOK @audit(level: "low")
+        }
+        "#;
+        let (files, datasource_files) = render_for_test(
+            schema,
+            r##"
+            [[directive_mapping]]
+            directive = "rateLimit"
+            attribute = "#[rate_limit(max = {max}, window = { window })]"
+            datasource_attribute = "#[tracing::instrument(name = {window})]"
+
+            [[directive_mapping]]
+            directive = "audit"
+            attribute = "#[audit(level = {level}, tags = \"{{}}\")]"
+            "##,
+        )
+        .unwrap();
+
+        let low = attribute("#[audit(level = \"low\", tags = \"{}\")]");
+        let objects = ParsedSource::of(&files, "objects.rs");
+        assert_eq!(
+            objects.structure("Query").attrs,
+            vec![
+                attribute("#[derive(Debug, Clone)]"),
+                attribute("#[audit(level = \"high\", tags = \"{}\")]")
+            ]
+        );
+        let items = objects.method("Query", "items");
+        assert_eq!(
+            items.attrs,
+            vec![attribute("#[rate_limit(max = 10, window = \"1m\")]")]
+        );
+        assert_eq!(argument(&items.sig, "first").attrs, vec![low.clone()]);
+        assert_eq!(objects.field("Item", "name").attrs, vec![low.clone()]);
+        assert!(objects.method("Item", "name").attrs.is_empty());
+        let enums = ParsedSource::of(&files, "enums.rs");
+        assert_eq!(enums.variant("Kind", "Book").attrs, vec![low]);
+
+        let datasource = ParsedSource::of(&datasource_files, "mod.rs");
+        let query_items = datasource.method("DataSource", "query_items");
+        assert_eq!(
+            query_items.attrs,
+            vec![attribute("#[tracing::instrument(name = \"1m\")]")]
+        );
+        assert!(argument(&query_items.sig, "_first").attrs.is_empty());
+
+        let result = render_for_test(
+            schema,
+            r##"
+            [[directive_mapping]]
+            directive = "rateLimit"
+            attribute = "#[rate_limit(burst = {burst})]"
+            "##,
+        );
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            r#"[objects] Query.items: invalid config entry [[directive_mapping]] directive = "rateLimit": @rateLimit has no argument burst here"#
+        );
+    }
+}
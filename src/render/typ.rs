@@ -1,11 +1,62 @@
 use super::super::parse::{self, *};
+use super::tokens::config_snippet_token;
 use super::RenderContext;
-use anyhow::Result;
+use crate::config::PrimitiveSetting;
+use crate::error::{Error, Result};
 use proc_macro2::TokenStream;
 use quote::*;
 
-pub fn value_type_def_token(
+const COPY_TYPES: &[&str] = &[
+    "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize", "f32",
+    "f64", "bool", "char",
+];
+
+/// The rust type of a primitive, `primitive_type` of the field or `[primitive]` of the config.
+/// `primitive_type` only applies to `Int`, `Float` and `ID`.
+pub fn primitive_rust_type(
+    primitive: &PrimitiveKind,
+    primitive_type: Option<&FieldPrimitiveType>,
+    render_context: &RenderContext,
+) -> String {
+    match (primitive, primitive_type) {
+        (PrimitiveKind::Int, Some(FieldPrimitiveType { rust_type, .. }))
+        | (PrimitiveKind::Float, Some(FieldPrimitiveType { rust_type, .. }))
+        | (PrimitiveKind::ID, Some(FieldPrimitiveType { rust_type, .. })) => rust_type.to_string(),
+        _ => render_context.config.primitive_type(primitive),
+    }
+}
+
+/// Whether a getter can return the rust type by copy instead of cloning it.
+pub fn is_copy_type(rust_type: &str) -> bool {
+    COPY_TYPES.contains(&rust_type)
+}
+
+/// The type of an input field or argument, `MaybeUndefined<T>` instead of `Option<T>` when it is
+/// nullable and `maybe_undefined`.
+pub fn input_value_type_token(
+    type_def: &parse::ValueTypeDef,
+    primitive_type: Option<&FieldPrimitiveType>,
+    maybe_undefined: bool,
+    schema: &StructuredSchema,
+    render_context: &RenderContext,
+) -> Result<TokenStream> {
+    if !maybe_undefined || !type_def.nullable() {
+        return field_type_token(type_def, primitive_type, schema, render_context);
+    }
+    let mut required = type_def.clone();
+    match &mut required {
+        parse::ValueTypeDef::Named(named_value) => named_value.is_nullable = false,
+        parse::ValueTypeDef::List(list_value) => list_value.is_nullable = false,
+    }
+    let inner_token = field_type_token(&required, primitive_type, schema, render_context)?;
+    Ok(quote! { MaybeUndefined<#inner_token> })
+}
+
+/// The rust type of a field, argument or list element. `primitive_type` of the field overrides the
+/// rust type of its primitive.
+pub fn field_type_token(
     type_def: &parse::ValueTypeDef,
+    primitive_type: Option<&FieldPrimitiveType>,
     schema: &StructuredSchema,
     render_context: &RenderContext,
 ) -> Result<TokenStream> {
@@ -13,7 +64,7 @@ pub fn value_type_def_token(
         parse::ValueTypeDef::Named(named_value) => {
             let nullable = named_value.is_nullable;
             let type_def = named_value.as_type_def(&schema.definitions)?;
-            let type_def = type_def_token(&type_def, &render_context)?;
+            let type_def = type_def_token(&type_def, primitive_type, render_context)?;
             if nullable {
                 quote! { Option<#type_def > }
             } else {
@@ -22,7 +73,8 @@ pub fn value_type_def_token(
         }
         parse::ValueTypeDef::List(list_value) => {
             let nullable = list_value.is_nullable;
-            let inner_token = value_type_def_token(&list_value.inner, schema, render_context)?;
+            let inner_token =
+                field_type_token(&list_value.inner, primitive_type, schema, render_context)?;
 
             if nullable {
                 quote! { Option<Vec<#inner_token>>}
@@ -36,13 +88,18 @@ pub fn value_type_def_token(
 
 fn type_def_token(
     type_def: &parse::TypeDef,
+    primitive_type: Option<&FieldPrimitiveType>,
     render_context: &RenderContext,
 ) -> Result<TokenStream> {
     //TODO() impl
     let result = match type_def {
         parse::TypeDef::Primitive(primitive) => {
-            let name = format_ident!("{}", primitive.rust_type());
-            quote! { #name }
+            let rust_type = primitive_rust_type(primitive, primitive_type, render_context);
+            let entry = match primitive_type {
+                Some(primitive_type) => primitive_type.entry.clone(),
+                None => PrimitiveSetting::entry_name(primitive),
+            };
+            config_snippet_token(&rust_type, &entry)?
         }
         parse::TypeDef::Object(object) => {
             let recursive = if let parse::TypeDef::InputObject(parent) = render_context.parent {
@@ -96,6 +153,232 @@ fn type_def_token(
             let name = format_ident!("{}", type_name);
             quote! { #name }
         }
+        parse::TypeDef::Mapped(mapped_type) => {
+            config_snippet_token(&mapped_type.rust_type, &mapped_type.entry_name)?
+        }
+        parse::TypeDef::Directive(directive) => {
+            return Err(Error::UndefinedType(directive.name_string()));
+        }
     };
     Ok(result)
 }
+
+#[cfg(test)]
+mod test {
+
+    use super::super::{argument, render_for_test, syntax, ParsedSource};
+    use super::*;
+    use crate::config::RendererConfig;
+
+    #[test]
+    pub fn use_mapped_rust_types() {
+        let schema = r#"
+        scalar DateTime
+
+        type Query {
+          createdAt: DateTime!
+          status: Status
+        }
+
+        enum Status {
+          REGISTERED
+        }
+        "#;
+        let (files, _) = render_for_test(
+            schema,
+            r#"
+            [[type_mapping]]
+            graphql_type = "DateTime"
+            rust_type = "DateTime<Utc>"
+            using = "use chrono::{DateTime, Utc}"
+
+            [[type_mapping]]
+            graphql_type = "Status"
+            rust_type = "domain::Status"
+            "#,
+        )
+        .unwrap();
+
+        let paths: Vec<String> = files
+            .iter()
+            .map(|file| file.path.display().to_string())
+            .collect();
+        assert_eq!(paths, vec!["objects.rs", "mod.rs"]);
+        let objects = ParsedSource::of(&files, "objects.rs");
+        assert!(objects.has_use("use chrono::{DateTime, Utc};"));
+        assert_eq!(
+            objects.field("Query", "created_at").ty,
+            syntax("DateTime<Utc>")
+        );
+        assert_eq!(
+            objects.field("Query", "status").ty,
+            syntax("Option<domain::Status>")
+        );
+    }
+
+    #[test]
+    pub fn render_configured_primitive_types() {
+        let schema = r#"
+        type Query {
+          user(id: ID!): User
+        }
+
+        type User {
+          id: ID!
+          age: Int!
+          score: Float!
+          rank: Int!
+        }
+        "#;
+        let (files, datasource_files) = render_for_test(
+            schema,
+            r#"
+            [primitive]
+            int = "i32"
+            id = "String"
+
+            [[field]]
+            target_type = "User"
+            target_field = "rank"
+            primitive_type = "u16"
+            "#,
+        )
+        .unwrap();
+        let objects = ParsedSource::of(&files, "objects.rs");
+        assert_eq!(
+            *objects.structure("User"),
+            syntax(
+                "#[derive(Debug, Clone)]
+                pub struct User { pub id: String, pub age: i32, pub score: f64, pub rank: u16, }"
+            )
+        );
+        assert_eq!(
+            *objects.method("User", "id"),
+            syntax("pub async fn id(&self) -> String { self.id.clone() }")
+        );
+        assert_eq!(
+            *objects.method("User", "rank"),
+            syntax("pub async fn rank(&self) -> u16 { self.rank }")
+        );
+        assert_eq!(
+            *argument(&objects.method("Query", "user").sig, "id").ty,
+            syntax("String")
+        );
+
+        let datasource = ParsedSource::of(&datasource_files, "mod.rs");
+        assert_eq!(
+            *argument(&datasource.method("DataSource", "query_user").sig, "_id").ty,
+            syntax("String")
+        );
+    }
+
+    #[test]
+    pub fn render_maybe_undefined_inputs() {
+        let schema = r#"
+        schema {
+          query: Query
+          mutation: Mutation
+        }
+
+        type Query {
+          users(name: String): [User!]!
+        }
+
+        type Mutation {
+          updateUser(name: String, age: Int): User!
+        }
+
+        type User {
+          name: String!
+        }
+
+        input UserInput {
+          name: String
+          age: Int
+          tags: [String]
+        }
+        "#;
+        let (files, datasource_files) = render_for_test(
+            schema,
+            r#"
+            maybe_undefined_types = ["Mutation", "UserInput"]
+
+            [[field]]
+            target_type = "UserInput"
+            target_field = "age"
+            maybe_undefined = false
+            "#,
+        )
+        .unwrap();
+
+        let objects = ParsedSource::of(&files, "objects.rs");
+        let users = &objects.method("Query", "users").sig;
+        assert_eq!(*argument(users, "name").ty, syntax("Option<String>"));
+        let update_user = &objects.method("Mutation", "update_user").sig;
+        assert_eq!(
+            *argument(update_user, "name").ty,
+            syntax("MaybeUndefined<String>")
+        );
+        assert_eq!(
+            *argument(update_user, "age").ty,
+            syntax("MaybeUndefined<i64>")
+        );
+        let input_objects = ParsedSource::of(&files, "input_objects.rs");
+        assert_eq!(
+            input_objects.field("UserInput", "name").ty,
+            syntax("MaybeUndefined<String>")
+        );
+        assert_eq!(
+            input_objects.field("UserInput", "age").ty,
+            syntax("Option<i64>")
+        );
+        assert_eq!(
+            input_objects.field("UserInput", "tags").ty,
+            syntax("MaybeUndefined<Vec<Option<String>>>")
+        );
+
+        let datasource = ParsedSource::of(&datasource_files, "mod.rs");
+        let update_user = &datasource.method("DataSource", "mutation_update_user").sig;
+        assert_eq!(
+            *argument(update_user, "_age").ty,
+            syntax("MaybeUndefined<i64>")
+        );
+    }
+
+    #[test]
+    pub fn name_the_field_entry_of_primitive_types() {
+        let schema = r#"
+        type User {
+          rank: Int!
+        }
+        "#;
+        let config: RendererConfig = toml::from_str(
+            r#"
+            [[field]]
+            target_type = "User"
+            target_field = "rank"
+            primitive_type = "Vec<u16)"
+            "#,
+        )
+        .unwrap();
+        let structured_schema = parse_schema(schema, &config).unwrap();
+        let user = &structured_schema.definitions.objects["User"];
+        let entry = r#"[[field]] target_type = "User", target_field = "rank""#;
+        let primitive_type = user.fields[0].primitive_type.as_ref().unwrap();
+        assert_eq!(primitive_type.rust_type, "Vec<u16)");
+        assert_eq!(primitive_type.entry, entry);
+
+        let context = RenderContext {
+            parent: TypeDef::Object(user),
+            config: &config,
+        };
+        let error = field_type_token(
+            &user.fields[0].typ,
+            Some(primitive_type),
+            &structured_schema,
+            &context,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains(entry), "{}", error);
+    }
+}
@@ -1,28 +1,20 @@
 use super::super::parse::*;
 use super::comment::*;
 use super::dependencies::*;
-use super::files::{fmt_file, pathbuf_to_str};
 use super::sorter::sort_by_line_pos_and_name;
 use super::tokens::*;
+use super::typ::field_type_token;
 use super::RenderContext;
-use anyhow::Result;
+use crate::config::{Phase, RendererConfig};
+use crate::error::{Result, ResultExt, Stage};
 use heck::CamelCase;
 use proc_macro2::TokenStream;
 use quote::*;
-use std::collections::HashSet;
-use std::fs::{self, OpenOptions};
-use std::io::{BufWriter, Write};
-use std::path::PathBuf;
-
-pub fn write_unions(output_dir: &str, structured_schema: &StructuredSchema) -> Result<bool> {
-    let mut output_file = PathBuf::from(output_dir);
-    output_file.push("unions.rs");
-    let file_path_str = pathbuf_to_str(&output_file);
-
-    if output_file.exists() {
-        fs::remove_file(&file_path_str)?;
-    }
 
+pub fn unions_source(
+    structured_schema: &StructuredSchema,
+    config: &RendererConfig,
+) -> Result<Option<String>> {
     let mut unions: Vec<&Union> = structured_schema
         .definitions
         .unions
@@ -30,15 +22,16 @@ pub fn write_unions(output_dir: &str, structured_schema: &StructuredSchema) -> R
         .into_iter()
         .collect();
     if unions.is_empty() {
-        return Ok(false);
+        return Ok(None);
     }
     unions.sort_by(sort_by_line_pos_and_name);
 
-    let mut all_dependencies = HashSet::<String>::new();
+    let mut all_dependencies = config_dependencies(config, Phase::Unions.module_name())?;
     let mut union_defs = Vec::<String>::new();
 
     for each_union in unions {
-        let (union_token, dependencies) = union_token(each_union, &structured_schema)?;
+        let (union_token, dependencies) = union_token(each_union, structured_schema, config)
+            .in_element(Stage::Unions, &each_union.name)?;
 
         union_defs.push(union_token.to_string());
 
@@ -47,39 +40,33 @@ pub fn write_unions(output_dir: &str, structured_schema: &StructuredSchema) -> R
         }
     }
 
-    let dest_file = OpenOptions::new()
-        .write(true)
-        .create(true)
-        .open(output_file.as_path())
-        .expect(format!("failed to open file : {}", file_path_str).as_ref());
-    let mut dest_file = BufWriter::new(dest_file);
-
-    dest_file.write(FILE_HEADER_COMMENT.as_bytes())?;
+    let mut source = String::new();
+    source.push_str(FILE_HEADER_COMMENT);
     let header = quote! {
         use async_graphql::*;
     };
 
-    dest_file.write(header.to_string().as_bytes())?;
+    source.push_str(&header.to_string());
     let dependencies_token = dependency_strs_to_token(all_dependencies);
 
-    dest_file.write(dependencies_token.to_string().as_bytes())?;
+    source.push_str(&dependencies_token.to_string());
     for each_union_def in union_defs {
-        dest_file.write(each_union_def.as_bytes())?;
+        source.push_str(&each_union_def);
     }
 
-    dest_file.flush()?;
-    fmt_file(file_path_str)?;
-    Ok(true)
+    Ok(Some(source))
 }
 
 fn union_token(
     union: &Union,
     schema: &StructuredSchema,
+    config: &RendererConfig,
 ) -> Result<(TokenStream, Vec<TokenStream>)> {
     let union_name = format_ident!("{}", union.name);
 
     let context = RenderContext {
         parent: TypeDef::Union(union),
+        config,
     };
 
     let UnionFieldsInfo {
@@ -88,9 +75,16 @@ fn union_token(
     } = union_fields_info(union.type_names.iter().collect(), schema, &context)?;
 
     let members = separate_by_comma(members);
+    let visible_attribute =
+        graphql_attribute_token(union.visible.iter().map(visible_token).collect());
+    let cfg = cfg_token(&union.features);
+    let directive_attributes = directive_attributes_token(&union.directives, config)?;
     let union_def = quote! {
 
+        #cfg
         #[derive(Union, Debug, Clone)]
+        #visible_attribute
+        #directive_attributes
         pub enum #union_name {
             #members
         }
@@ -144,7 +138,6 @@ fn convert_union_member(
     schema: &StructuredSchema,
     render_context: &RenderContext,
 ) -> Result<UnionMember> {
-    let member_type_name = format_ident!("{}", member);
     let member_enum_name = format_ident!("{}", member.to_camel_case());
 
     //TODO(tacogips) this conversion of union member type to ValueTypeDef might be a bit hack-y?
@@ -152,12 +145,84 @@ fn convert_union_member(
         value_type_name: member.to_string(),
         is_nullable: false,
     });
+    // a mapped member is its rust type, imported with the `using` of the mapping.
+    let member_type_name = field_type_token(&member_type, None, schema, render_context)?;
     let dependencies = dependency(&member_type, schema, render_context)?;
 
-    let member = quote! { #member_enum_name (#member_type_name) };
+    let cfg = cfg_token(&member_features(member, schema, render_context));
+    let member = quote! { #cfg #member_enum_name (#member_type_name) };
 
     Ok(UnionMember {
         member,
         dependencies,
     })
 }
+
+/// The features of the member object the union or the interface is not gated by.
+pub fn member_features(
+    member: &str,
+    schema: &StructuredSchema,
+    render_context: &RenderContext,
+) -> Vec<String> {
+    let parent_features = match render_context.parent {
+        TypeDef::Union(union) => &union.features,
+        TypeDef::Interface(interface) => &interface.features,
+        _ => return vec![],
+    };
+    schema
+        .definitions
+        .objects
+        .get(member)
+        .map(|object| {
+            object
+                .features
+                .iter()
+                .filter(|feature| !parent_features.contains(feature))
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    pub fn render_mapped_members() {
+        let schema = r#"
+        type Query {
+          search: [SearchResult!]!
+        }
+
+        union SearchResult = User | Post
+
+        type User {
+          id: ID!
+        }
+
+        type Post {
+          id: ID!
+        }
+        "#;
+        let config: RendererConfig = toml::from_str(
+            r#"
+            [[type_mapping]]
+            graphql_type = "Post"
+            rust_type = "blog::Post"
+            using = "use crate::blog"
+            "#,
+        )
+        .unwrap();
+        let structured_schema = parse_schema(schema, &config).unwrap();
+        let source = unions_source(&structured_schema, &config)
+            .unwrap()
+            .unwrap()
+            .replace(' ', "");
+
+        assert!(source.contains("usecrate::blog;"));
+        assert!(source.contains("Post(blog::Post)"));
+        assert!(source.contains("User(User)"));
+    }
+}
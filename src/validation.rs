@@ -0,0 +1,1179 @@
+//! Checks the config against the schema, so misspelled keys and names are reported
+//! instead of being silently ignored.
+
+use crate::config::{
+    scalar_preset_names, FieldSetting, Located, Phase, PrimitiveSetting, RendererConfig, Visible,
+};
+use crate::error::{Error, Result};
+use crate::parse::ignoring::tags::schema_tags;
+use crate::parse::typed_ids::typed_id_name;
+use crate::parse::{
+    applied_directives, Definitions, NamedValue, PrimitiveKind, Scalar, StructuredSchema, TypeDef,
+    ValueTypeDef,
+};
+use crate::render::{BackingType, ResolverType};
+use heck::CamelCase;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use syn::parse::{ParseStream, Parser};
+use syn::punctuated::Punctuated;
+use syn::Token;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticKind {
+    /// A key the config does not know, usually a typo.
+    UnknownKey,
+    /// A type, field or enum value that is not defined in the schema.
+    UnknownTarget,
+    InvalidValue,
+    /// The target exists but is removed by `[ignore]`, so the entry has no effect.
+    NeverApplied,
+}
+
+/// A problem with a single entry of the config. `entry` names the entry as it is written in the config file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigDiagnostic {
+    pub kind: DiagnosticKind,
+    pub entry: String,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.entry, self.message)
+    }
+}
+
+/// Validate the config and log the problems as warnings, or fail with all of them in strict mode.
+/// `parsed` is the schema before `[ignore]` is applied and `filtered` the one after. The tags are not
+/// applied to either, an entry of a target the tags remove still applies to the other variants.
+pub fn check_config(
+    config: &RendererConfig,
+    parsed: &StructuredSchema,
+    filtered: &StructuredSchema,
+) -> Result<()> {
+    let diagnostics = validate_config(config, parsed, filtered);
+    if diagnostics.is_empty() {
+        return Ok(());
+    }
+    if config.strict {
+        return Err(Error::ConfigValidation(diagnostics));
+    }
+    for diagnostic in diagnostics.iter() {
+        log::warn!("{}", diagnostic);
+    }
+    Ok(())
+}
+
+pub fn validate_config(
+    config: &RendererConfig,
+    parsed: &StructuredSchema,
+    filtered: &StructuredSchema,
+) -> Vec<ConfigDiagnostic> {
+    let mut validator = Validator {
+        parsed: &parsed.definitions,
+        filtered: &filtered.definitions,
+        diagnostics: vec![],
+    };
+
+    for key in config.unknown_keys.iter() {
+        validator.push(DiagnosticKind::UnknownKey, key, "unknown key");
+    }
+
+    let using_keys = RendererConfig::using_keys();
+    let mut using = config
+        .using
+        .iter()
+        .flatten()
+        .map(|(key, _)| key)
+        .collect::<Vec<&String>>();
+    using.sort();
+    for key in using {
+        if !using_keys.contains(&key.as_str()) {
+            validator.push(
+                DiagnosticKind::UnknownKey,
+                format!("[using] {}", key),
+                format!("unknown key, expected one of {}", using_keys.join(", ")),
+            );
+        }
+    }
+
+    if let Some(resolver_type) = &config.resolver_type {
+        validator.check_resolver_type("resolver_type", resolver_type);
+    }
+
+    // the targets of the `@reverse` settings are where they are written.
+    for resolver in config.resolver.iter().flatten() {
+        let entry = resolver.entry_name();
+        if !resolver.inline && validator.check_object(&entry, &resolver.target_type) {
+            validator.check_object_field(&entry, &resolver.target_type, &resolver.target_field);
+        }
+        if let Some(resolver_type) = &resolver.resolver_type {
+            validator.check_resolver_type(&entry, resolver_type);
+        }
+    }
+
+    for rule in config.resolver_rule.iter().flatten() {
+        let entry = rule.entry_name();
+        match &rule.resolver_type {
+            Some(resolver_type) => validator.check_resolver_type(&entry, resolver_type),
+            None if rule.attribute.is_none() => validator.push(
+                DiagnosticKind::InvalidValue,
+                &entry,
+                "neither resolver_type nor attribute is set",
+            ),
+            None => {}
+        }
+        let definitions = validator.parsed;
+        let matches_any = definitions.objects.values().any(|object| {
+            object.fields.iter().any(|field| {
+                rule.matches(&object.name, field, definitions)
+                    .unwrap_or(false)
+            })
+        });
+        if !matches_any {
+            validator.push(
+                DiagnosticKind::UnknownTarget,
+                &entry,
+                "no field of the objects in the schema matches",
+            );
+        }
+    }
+
+    for field in config.field.iter().flatten() {
+        let entry = field.entry_name();
+        if !field.inline {
+            validator.check_any_field(&entry, &field.target_type, &field.target_field);
+        }
+        if field.primitive_type.is_some() {
+            validator.check_primitive_field(&entry, &field.target_type, &field.target_field);
+        }
+        validator.check_id_of(field);
+        validator.check_secret(field);
+    }
+
+    for hidden_field in config.hidden_field.iter().flatten() {
+        validator.check_object(&hidden_field.entry_name(), &hidden_field.target_type);
+    }
+
+    for additional_resolver in config.additional_resolver.iter().flatten() {
+        validator.check_object(
+            &additional_resolver.entry_name(),
+            &additional_resolver.target_type,
+        );
+    }
+
+    for enum_setting in config.r#enum.iter().flatten().filter(|each| !each.inline) {
+        let entry = enum_setting.entry_name();
+        let values = enum_setting
+            .value
+            .iter()
+            .flatten()
+            .map(|value| value.value.as_str())
+            .collect::<Vec<&str>>();
+        validator.check_enum(&entry, &enum_setting.target_enum, &values);
+    }
+
+    if let Some(ignore) = &config.ignore {
+        let defined = validator.parsed;
+        let ignored = [
+            ("object", &ignore.object, names(&defined.objects)),
+            ("enum", &ignore.r#enum, names(&defined.enums)),
+            (
+                "input_object",
+                &ignore.input_object,
+                names(&defined.input_objects),
+            ),
+            ("union", &ignore.union, names(&defined.unions)),
+            ("interface", &ignore.interface, names(&defined.interfaces)),
+            ("scalar", &ignore.scalar, names(&defined.scalars)),
+        ];
+        let mut diagnostics = vec![];
+        for (key, ignored_names, defined_names) in ignored.iter() {
+            for name in ignored_names.iter().flatten() {
+                if !defined_names.contains(&name) && !defined.mapped_types.contains_key(name) {
+                    diagnostics.push(ConfigDiagnostic {
+                        kind: DiagnosticKind::UnknownTarget,
+                        entry: format!("[ignore] {} = {:?}", key, name),
+                        message: format!("{} {} is not defined in the schema", key, name),
+                    });
+                }
+            }
+        }
+        validator.diagnostics.append(&mut diagnostics);
+    }
+
+    let tags = schema_tags(validator.parsed);
+    let tag_entries = [
+        ("include_tags", &config.include_tags),
+        ("exclude_tags", &config.exclude_tags),
+    ];
+    for (key, names) in tag_entries.iter() {
+        for name in names.iter() {
+            if !tags.contains(name) {
+                validator.push(
+                    DiagnosticKind::UnknownTarget,
+                    format!("{} = {:?}", key, name),
+                    format!("no element of the schema is tagged {}", name),
+                );
+            }
+        }
+    }
+
+    let applied_directives = applied_directives(validator.parsed);
+    for mapping in config.directive_mapping.iter().flatten() {
+        if mapping.attribute.is_none() && mapping.datasource_attribute.is_none() {
+            validator.push(
+                DiagnosticKind::InvalidValue,
+                mapping.entry_name(),
+                "neither attribute nor datasource_attribute is set",
+            );
+        }
+        if !applied_directives
+            .iter()
+            .any(|(directive, _)| directive.name == mapping.directive)
+        {
+            validator.push(
+                DiagnosticKind::UnknownTarget,
+                mapping.entry_name(),
+                format!("@{} is not applied in the schema", mapping.directive),
+            );
+        }
+    }
+
+    for preset in config.scalar_presets.iter() {
+        let presets = scalar_preset_names();
+        if !presets.contains(&preset.as_str()) {
+            validator.push(
+                DiagnosticKind::InvalidValue,
+                format!("scalar_presets = {:?}", preset),
+                format!("unknown preset, expected one of {}", presets.join(", ")),
+            );
+        }
+    }
+
+    for type_mapping in config.type_mapping.iter().flatten() {
+        if !validator
+            .parsed
+            .mapped_types
+            .contains_key(&type_mapping.graphql_type)
+        {
+            validator.push(
+                DiagnosticKind::UnknownTarget,
+                type_mapping.entry_name(),
+                format!(
+                    "type {} is not defined in the schema",
+                    type_mapping.graphql_type
+                ),
+            );
+        }
+    }
+
+    for scalar in config.scalar.iter().flatten() {
+        let entry = scalar.entry_name();
+        let name = &scalar.target_scalar;
+        if validator.parsed.mapped_types.contains_key(name) {
+            validator.push(
+                DiagnosticKind::NeverApplied,
+                entry,
+                format!("scalar {} is mapped to an existing rust type", name),
+            );
+        } else if !validator.parsed.scalars.contains_key(name) {
+            validator.push(
+                DiagnosticKind::UnknownTarget,
+                entry,
+                format!("scalar {} is not defined in the schema", name),
+            );
+        } else if !validator.filtered.scalars.contains_key(name) {
+            validator.push(
+                DiagnosticKind::NeverApplied,
+                entry,
+                format!("scalar {} is ignored", name),
+            );
+        } else if let Some(backing_type) = &scalar.backing_type {
+            if BackingType::from_str(backing_type).is_err() {
+                validator.push(
+                    DiagnosticKind::InvalidValue,
+                    entry,
+                    format!(
+                        "unknown backing_type {:?}, expected one of String, i64, f64, bool or serde_json::Value",
+                        backing_type
+                    ),
+                );
+            }
+        }
+    }
+
+    for type_setting in config.r#type.iter().flatten() {
+        let entry = type_setting.entry_name();
+        let type_name = &type_setting.target_type;
+        let is_defined = |definitions: &Definitions| {
+            definitions.objects.contains_key(type_name)
+                || definitions.input_objects.contains_key(type_name)
+                || definitions.enums.contains_key(type_name)
+                || definitions.interfaces.contains_key(type_name)
+                || definitions.unions.contains_key(type_name)
+                || definitions.scalars.contains_key(type_name)
+        };
+        if !is_defined(validator.parsed) {
+            let message = if validator.parsed.mapped_types.contains_key(type_name) {
+                format!("type {} is mapped to an existing rust type", type_name)
+            } else {
+                format!("type {} is not defined in the schema", type_name)
+            };
+            validator.push(DiagnosticKind::UnknownTarget, entry, message);
+        } else if !is_defined(validator.filtered) {
+            validator.push(
+                DiagnosticKind::NeverApplied,
+                entry,
+                format!("type {} is ignored", type_name),
+            );
+        } else if type_setting.feature.is_some()
+            && [
+                &parsed.query_name,
+                &parsed.mutation_name,
+                &parsed.subscription_name,
+            ]
+            .iter()
+            .any(|root| root.as_ref() == Some(type_name))
+        {
+            validator.push(
+                DiagnosticKind::InvalidValue,
+                entry,
+                format!(
+                    "{} is a root operation type of schema_builder() and can't be gated by a feature",
+                    type_name
+                ),
+            );
+        }
+    }
+
+    for type_name in config.maybe_undefined_types.iter() {
+        let entry = format!("maybe_undefined_types = {:?}", type_name);
+        let is_defined = |definitions: &Definitions| {
+            definitions.objects.contains_key(type_name)
+                || definitions.input_objects.contains_key(type_name)
+        };
+        if !is_defined(validator.parsed) {
+            validator.push(
+                DiagnosticKind::UnknownTarget,
+                entry,
+                format!(
+                    "object or input object {} is not defined in the schema",
+                    type_name
+                ),
+            );
+        } else if !is_defined(validator.filtered) {
+            validator.push(
+                DiagnosticKind::NeverApplied,
+                entry,
+                format!("type {} is ignored", type_name),
+            );
+        }
+    }
+
+    for member_type in config.custom_member_types.iter().flatten() {
+        let named = NamedValue {
+            value_type_name: member_type.to_string(),
+            is_nullable: true,
+        };
+        if named.as_type_def(validator.parsed).is_err() {
+            validator.push(
+                DiagnosticKind::UnknownTarget,
+                format!("custom_member_types = {:?}", member_type),
+                format!("type {} is not defined in the schema", member_type),
+            );
+        }
+    }
+
+    validator.diagnostics
+}
+
+/// What a rust snippet written in the config is pasted into the generated code as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnippetKind {
+    /// `header`, any items.
+    Items,
+    /// `[using]` values and `using` of `[[additional_resolver]]` and `[[hidden_field]]`.
+    /// The generator adds the trailing `;`.
+    Use,
+    /// `[[additional_resolver]] body`, methods of the `#[Object]` impl.
+    Methods,
+    /// `[[hidden_field]] field_def`, a named field of the struct.
+    Field,
+    /// `[[resolver]] attribute`, outer attributes of the resolver method.
+    Attributes,
+    /// `additional_attributes` and `[[scalar]] derives`, names added to `#[derive(..)]`.
+    Derives,
+    /// `data_source_fetch_method`, the expression the data source is taken from.
+    Expr,
+    /// `[module_path]` values and `visible` function names.
+    Path,
+    /// `[[type_mapping]] rust_type`, `[primitive]` values and `[[field]] primitive_type`.
+    Type,
+}
+
+impl fmt::Display for SnippetKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SnippetKind::Items => "rust items",
+            SnippetKind::Use => "use declaration",
+            SnippetKind::Methods => "methods",
+            SnippetKind::Field => "field definition",
+            SnippetKind::Attributes => "attributes",
+            SnippetKind::Derives => "derive list",
+            SnippetKind::Expr => "expression",
+            SnippetKind::Path => "path",
+            SnippetKind::Type => "type",
+        };
+        f.write_str(name)
+    }
+}
+
+impl SnippetKind {
+    pub fn parse(&self, snippet: &str) -> syn::Result<()> {
+        match self {
+            SnippetKind::Items => syn::parse_str::<syn::File>(snippet).map(|_| ()),
+            SnippetKind::Use => {
+                let parser = |input: ParseStream| -> syn::Result<()> {
+                    input.parse::<Token![use]>()?;
+                    input.parse::<syn::UseTree>()?;
+                    if input.peek(Token![;]) {
+                        return Err(
+                            input.error("remove the trailing `;`, it is added on rendering")
+                        );
+                    }
+                    Ok(())
+                };
+                parser.parse_str(snippet)
+            }
+            SnippetKind::Methods => {
+                let parser = |input: ParseStream| -> syn::Result<()> {
+                    while !input.is_empty() {
+                        input.parse::<syn::ImplItemMethod>()?;
+                    }
+                    Ok(())
+                };
+                parser.parse_str(snippet)
+            }
+            SnippetKind::Field => syn::Field::parse_named.parse_str(snippet).map(|_| ()),
+            SnippetKind::Attributes => syn::Attribute::parse_outer.parse_str(snippet).map(|_| ()),
+            SnippetKind::Derives => {
+                let paths =
+                    Punctuated::<syn::Path, Token![,]>::parse_terminated.parse_str(snippet)?;
+                if paths.is_empty() {
+                    return Err(syn::Error::new(
+                        proc_macro2::Span::call_site(),
+                        "expected at least one name",
+                    ));
+                }
+                Ok(())
+            }
+            SnippetKind::Expr => syn::parse_str::<syn::Expr>(snippet).map(|_| ()),
+            SnippetKind::Path => syn::Path::parse_mod_style.parse_str(snippet).map(|_| ()),
+            SnippetKind::Type => syn::parse_str::<syn::Type>(snippet).map(|_| ()),
+        }
+    }
+}
+
+/// Parse every rust snippet of the config as what it is used for, so a broken snippet is reported
+/// with its entry and line instead of producing a broken file.
+pub fn check_snippets(config: &RendererConfig) -> Result<()> {
+    let mut snippets: Vec<(String, SnippetKind, &Located<String>)> = vec![
+        (
+            "[module_path] data_source".to_string(),
+            SnippetKind::Path,
+            &config.module_path.data_source,
+        ),
+        (
+            "data_source_fetch_method".to_string(),
+            SnippetKind::Expr,
+            &config.data_source_fetch_method,
+        ),
+    ];
+    if let Some(header) = &config.header {
+        snippets.push(("header".to_string(), SnippetKind::Items, header));
+    }
+    for phase in Phase::all().iter() {
+        snippets.push((
+            format!("[module_path] {}", phase.module_name()),
+            SnippetKind::Path,
+            config.module_path.of(phase),
+        ));
+    }
+    if let Some(attributes) = &config.additional_attributes {
+        snippets.push((
+            "additional_attributes".to_string(),
+            SnippetKind::Derives,
+            attributes,
+        ));
+    }
+    let mut using = config
+        .using
+        .iter()
+        .flatten()
+        .collect::<Vec<(&String, &Located<String>)>>();
+    using.sort_by_key(|(key, _)| *key);
+    for (key, declarations) in using {
+        snippets.push((format!("[using] {}", key), SnippetKind::Use, declarations));
+    }
+    for resolver in config.resolver.iter().flatten() {
+        if let Some(attribute) = &resolver.attribute {
+            snippets.push((resolver.entry_name(), SnippetKind::Attributes, attribute));
+        }
+    }
+    for rule in config.resolver_rule.iter().flatten() {
+        if let Some(attribute) = &rule.attribute {
+            snippets.push((rule.entry_name(), SnippetKind::Attributes, attribute));
+        }
+    }
+    for additional_resolver in config.additional_resolver.iter().flatten() {
+        let entry = additional_resolver.entry_name();
+        snippets.push((
+            entry.clone(),
+            SnippetKind::Methods,
+            &additional_resolver.body,
+        ));
+        if let Some(using) = &additional_resolver.using {
+            snippets.push((entry, SnippetKind::Use, using));
+        }
+    }
+    for kind in [PrimitiveKind::Int, PrimitiveKind::Float, PrimitiveKind::ID].iter() {
+        if let Some(rust_type) = config.primitive.get(kind) {
+            snippets.push((
+                PrimitiveSetting::entry_name(kind),
+                SnippetKind::Type,
+                rust_type,
+            ));
+        }
+    }
+    for field in config.field.iter().flatten() {
+        if let Some(primitive_type) = &field.primitive_type {
+            snippets.push((field.entry_name(), SnippetKind::Type, primitive_type));
+        }
+    }
+    for type_mapping in config.type_mapping.iter().flatten() {
+        let entry = type_mapping.entry_name();
+        snippets.push((entry.clone(), SnippetKind::Type, &type_mapping.rust_type));
+        if let Some(using) = &type_mapping.using {
+            snippets.push((entry, SnippetKind::Use, using));
+        }
+    }
+    for scalar in config.scalar.iter().flatten() {
+        if let Some(derives) = &scalar.derives {
+            snippets.push((scalar.entry_name(), SnippetKind::Derives, derives));
+        }
+    }
+    for hidden_field in config.hidden_field.iter().flatten() {
+        let entry = hidden_field.entry_name();
+        snippets.push((entry.clone(), SnippetKind::Field, &hidden_field.field_def));
+        if let Some(using) = &hidden_field.using {
+            snippets.push((entry, SnippetKind::Use, using));
+        }
+    }
+
+    let snippet_error = |entry: String, kind: SnippetKind, span, message: String| Error::Snippet {
+        entry,
+        expected: kind.to_string(),
+        location: config.location(span),
+        message,
+    };
+    for (entry, kind, snippet) in snippets {
+        // `[using]` holds several declarations separated by `;`.
+        let parts: Vec<&str> = if kind == SnippetKind::Use {
+            snippet
+                .split(';')
+                .map(str::trim)
+                .filter(|each| !each.is_empty())
+                .collect()
+        } else {
+            vec![snippet]
+        };
+        for part in parts {
+            kind.parse(part)
+                .map_err(|e| snippet_error(entry.clone(), kind, snippet.span(), e.to_string()))?;
+        }
+    }
+
+    let visible_functions = config
+        .r#type
+        .iter()
+        .flatten()
+        .map(|each| (each.entry_name(), &each.visible))
+        .chain(
+            config
+                .field
+                .iter()
+                .flatten()
+                .map(|each| (each.entry_name(), &each.visible)),
+        )
+        .chain(config.r#enum.iter().flatten().flat_map(|each| {
+            each.value
+                .iter()
+                .flatten()
+                .map(move |value| (each.value_entry_name(value), &value.visible))
+        }));
+    for (entry, visible) in visible_functions {
+        if let Some(visible) = visible {
+            if let Visible::Function(function) = &**visible {
+                SnippetKind::Path.parse(function).map_err(|e| {
+                    snippet_error(entry, SnippetKind::Path, visible.span(), e.to_string())
+                })?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn names<T>(definitions: &HashMap<String, T>) -> Vec<&String> {
+    definitions.keys().collect()
+}
+
+struct Validator<'a> {
+    parsed: &'a Definitions,
+    filtered: &'a Definitions,
+    diagnostics: Vec<ConfigDiagnostic>,
+}
+
+impl<'a> Validator<'a> {
+    fn push<E: ToString, M: ToString>(&mut self, kind: DiagnosticKind, entry: E, message: M) {
+        self.diagnostics.push(ConfigDiagnostic {
+            kind,
+            entry: entry.to_string(),
+            message: message.to_string(),
+        })
+    }
+
+    fn check_resolver_type(&mut self, entry: &str, resolver_type: &str) {
+        if ResolverType::from_str(resolver_type).is_err() {
+            self.push(
+                DiagnosticKind::InvalidValue,
+                entry,
+                format!(
+                    "invalid resolver_type \"{}\", expected \"method\" or \"field\"",
+                    resolver_type
+                ),
+            );
+        }
+    }
+
+    /// Returns whether the object is rendered, so the checks of its fields make sense.
+    fn check_object(&mut self, entry: &str, type_name: &str) -> bool {
+        if !self.parsed.objects.contains_key(type_name) {
+            self.push(
+                DiagnosticKind::UnknownTarget,
+                entry,
+                format!("object {} is not defined in the schema", type_name),
+            );
+            false
+        } else if !self.filtered.objects.contains_key(type_name) {
+            self.push(
+                DiagnosticKind::NeverApplied,
+                entry,
+                format!("object {} is ignored", type_name),
+            );
+            false
+        } else {
+            true
+        }
+    }
+
+    fn check_object_field(&mut self, entry: &str, type_name: &str, field_name: &str) {
+        let has_field = |definitions: &Definitions| {
+            definitions.objects.get(type_name).map_or(false, |object| {
+                object.fields.iter().any(|f| f.name == field_name)
+            })
+        };
+        self.check_field(entry, type_name, field_name, has_field);
+    }
+
+    /// `[[field]]` applies to the fields of objects, interfaces and input objects.
+    fn check_any_field(&mut self, entry: &str, type_name: &str, field_name: &str) {
+        let is_defined = |definitions: &Definitions| {
+            definitions.objects.contains_key(type_name)
+                || definitions.interfaces.contains_key(type_name)
+                || definitions.input_objects.contains_key(type_name)
+        };
+        if !is_defined(self.parsed) {
+            self.push(
+                DiagnosticKind::UnknownTarget,
+                entry,
+                format!(
+                    "object, interface or input object {} is not defined in the schema",
+                    type_name
+                ),
+            );
+            return;
+        }
+        if !is_defined(self.filtered) {
+            self.push(
+                DiagnosticKind::NeverApplied,
+                entry,
+                format!("type {} is ignored", type_name),
+            );
+            return;
+        }
+
+        let has_field = |definitions: &Definitions| {
+            let object_fields = definitions
+                .objects
+                .get(type_name)
+                .map(|object| &object.fields)
+                .or_else(|| {
+                    definitions
+                        .interfaces
+                        .get(type_name)
+                        .map(|intf| &intf.fields)
+                });
+            match object_fields {
+                Some(fields) => fields.iter().any(|f| f.name == field_name),
+                None => definitions
+                    .input_objects
+                    .get(type_name)
+                    .map_or(false, |input| {
+                        input.fields.iter().any(|f| f.name == field_name)
+                    }),
+            }
+        };
+        self.check_field(entry, type_name, field_name, has_field);
+    }
+
+    /// The type of a field of an object, interface or input object.
+    fn field_type(&self, type_name: &str, field_name: &str) -> Option<&'a ValueTypeDef> {
+        let definitions = self.parsed;
+        definitions
+            .objects
+            .get(type_name)
+            .map(|object| &object.fields)
+            .or_else(|| {
+                definitions
+                    .interfaces
+                    .get(type_name)
+                    .map(|intf| &intf.fields)
+            })
+            .and_then(|fields| fields.iter().find(|f| f.name == field_name))
+            .map(|field| &field.typ)
+            .or_else(|| {
+                definitions
+                    .input_objects
+                    .get(type_name)
+                    .and_then(|input| input.fields.iter().find(|f| f.name == field_name))
+                    .map(|field| &field.typ)
+            })
+    }
+
+    /// `primitive_type` only applies to the fields of `Int`, `Float` and `ID`.
+    fn check_primitive_field(&mut self, entry: &str, type_name: &str, field_name: &str) {
+        let type_def = match self
+            .field_type(type_name, field_name)
+            .map(|typ| typ.element_value_type_def(self.parsed))
+        {
+            Some(Ok(type_def)) => type_def,
+            _ => return,
+        };
+        match type_def {
+            TypeDef::Primitive(PrimitiveKind::Int)
+            | TypeDef::Primitive(PrimitiveKind::Float)
+            | TypeDef::Primitive(PrimitiveKind::ID) => {}
+            TypeDef::Scalar(Scalar { id_of: Some(_), .. }) => self.push(
+                DiagnosticKind::NeverApplied,
+                entry,
+                format!(
+                    "{}.{} is a typed id, primitive_type is not applied",
+                    type_name, field_name
+                ),
+            ),
+            _ => self.push(
+                DiagnosticKind::InvalidValue,
+                entry,
+                format!(
+                    "primitive_type needs a field of Int, Float or ID, {}.{} is {}",
+                    type_name,
+                    field_name,
+                    type_def.name()
+                ),
+            ),
+        }
+    }
+
+    /// `secret` applies to input fields and `secret_arguments` to the arguments of object fields.
+    fn check_secret(&mut self, field: &FieldSetting) {
+        let entry = field.entry_name();
+        if field.secret.is_some() && !self.parsed.input_objects.contains_key(&field.target_type) {
+            self.push(
+                DiagnosticKind::InvalidValue,
+                &entry,
+                format!(
+                    "secret applies to input fields, {} is not an input object",
+                    field.target_type
+                ),
+            );
+        }
+        let secret_arguments = match &field.secret_arguments {
+            Some(secret_arguments) => secret_arguments,
+            None => return,
+        };
+        let arguments = self
+            .parsed
+            .objects
+            .get(&field.target_type)
+            .and_then(|object| object.fields.iter().find(|f| f.name == field.target_field))
+            .map(|f| &f.arguments);
+        let arguments = match arguments {
+            Some(arguments) => arguments,
+            None => return,
+        };
+        for argument_name in secret_arguments {
+            if !arguments.iter().any(|arg| &arg.name == argument_name) {
+                self.push(
+                    DiagnosticKind::UnknownTarget,
+                    &entry,
+                    format!(
+                        "argument {} of {}.{} is not defined in the schema",
+                        argument_name, field.target_type, field.target_field
+                    ),
+                );
+            }
+        }
+    }
+
+    /// `id_of` and `argument_id_of` name objects and tie `ID` fields and arguments to them.
+    fn check_id_of(&mut self, field: &FieldSetting) {
+        let entry = field.entry_name();
+        let mut owners: Vec<(&String, &String)> = field
+            .id_of
+            .iter()
+            .map(|owner| (&field.target_field, owner))
+            .collect();
+        let mut argument_owners: Vec<(&String, &String)> =
+            field.argument_id_of.iter().flatten().collect();
+        argument_owners.sort();
+
+        for (_, owner) in owners.iter().chain(argument_owners.iter()) {
+            if !self.parsed.objects.contains_key(*owner) {
+                self.push(
+                    DiagnosticKind::UnknownTarget,
+                    &entry,
+                    format!("object {} is not defined in the schema", owner),
+                );
+            }
+        }
+
+        if let Some((field_name, owner)) = owners.pop() {
+            if let Some(typ) = self.field_type(&field.target_type, field_name) {
+                self.check_typed_id(
+                    &entry,
+                    typ,
+                    owner,
+                    &format!("{}.{}", field.target_type, field_name),
+                );
+            }
+        }
+
+        let arguments = self
+            .parsed
+            .objects
+            .get(&field.target_type)
+            .map(|object| &object.fields)
+            .or_else(|| {
+                self.parsed
+                    .interfaces
+                    .get(&field.target_type)
+                    .map(|intf| &intf.fields)
+            })
+            .and_then(|fields| fields.iter().find(|f| f.name == field.target_field))
+            .map(|f| &f.arguments);
+        let arguments = match arguments {
+            Some(arguments) => arguments,
+            None => return,
+        };
+        for (argument_name, owner) in argument_owners {
+            let element = format!(
+                "argument {} of {}.{}",
+                argument_name, field.target_type, field.target_field
+            );
+            match arguments.iter().find(|arg| &arg.name == argument_name) {
+                Some(argument) => self.check_typed_id(&entry, &argument.typ, owner, &element),
+                None => self.push(
+                    DiagnosticKind::UnknownTarget,
+                    &entry,
+                    format!("{} is not defined in the schema", element),
+                ),
+            }
+        }
+    }
+
+    fn check_typed_id(&mut self, entry: &str, typ: &ValueTypeDef, owner: &str, element: &str) {
+        if !self.parsed.objects.contains_key(owner) {
+            return;
+        }
+        match typ.element_value_type_def(self.parsed) {
+            Ok(TypeDef::Scalar(Scalar {
+                id_of: Some(id_of), ..
+            })) if id_of == owner => {}
+            Ok(TypeDef::Primitive(PrimitiveKind::ID)) => self.push(
+                DiagnosticKind::NeverApplied,
+                entry,
+                format!(
+                    "{} is not typed, {} is defined in the schema",
+                    element,
+                    typed_id_name(owner)
+                ),
+            ),
+            Ok(type_def) => self.push(
+                DiagnosticKind::InvalidValue,
+                entry,
+                format!("{} is {}, not an ID", element, type_def.name()),
+            ),
+            Err(_) => {}
+        }
+    }
+
+    fn check_field<F: Fn(&Definitions) -> bool>(
+        &mut self,
+        entry: &str,
+        type_name: &str,
+        field_name: &str,
+        has_field: F,
+    ) {
+        if !has_field(self.parsed) {
+            self.push(
+                DiagnosticKind::UnknownTarget,
+                entry,
+                format!(
+                    "field {}.{} is not defined in the schema",
+                    type_name, field_name
+                ),
+            );
+        } else if !has_field(self.filtered) {
+            self.push(
+                DiagnosticKind::NeverApplied,
+                entry,
+                format!(
+                    "field {}.{} is removed because its type is ignored",
+                    type_name, field_name
+                ),
+            );
+        }
+    }
+
+    /// Enum settings are looked up by the rust names of the enum and its values.
+    fn check_enum(&mut self, entry: &str, enum_name: &str, values: &[&str]) {
+        let find = |definitions: &'a Definitions| {
+            definitions
+                .enums
+                .values()
+                .find(|enm| enm.name.to_camel_case() == enum_name)
+        };
+        let enm = match find(self.parsed) {
+            Some(enm) => enm,
+            None => {
+                self.push(
+                    DiagnosticKind::UnknownTarget,
+                    entry,
+                    format!("enum {} is not defined in the schema", enum_name),
+                );
+                return;
+            }
+        };
+        if find(self.filtered).is_none() {
+            self.push(
+                DiagnosticKind::NeverApplied,
+                entry,
+                format!("enum {} is ignored", enum_name),
+            );
+            return;
+        }
+
+        for value in values {
+            if !enm
+                .values
+                .iter()
+                .any(|each| each.value_name.to_camel_case() == *value)
+            {
+                self.push(
+                    DiagnosticKind::UnknownTarget,
+                    format!("{}, value = {:?}", entry, value),
+                    format!(
+                        "enum value {}.{} is not defined in the schema",
+                        enum_name, value
+                    ),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::parse::parse_schema;
+
+    #[test]
+    pub fn report_unknown_and_ignored_targets() {
+        let schema = r#"
+        type Query {
+          me: User!
+          hidden: Hidden
+        }
+
+        type User {
+          name: String!
+        }
+
+        type Hidden {
+          id: ID!
+        }
+        "#;
+        let config: RendererConfig = toml::from_str(
+            r#"
+            [[resolver]]
+            target_type = "User"
+            target_field = "nmae"
+
+            [[resolver]]
+            target_type = "Query"
+            target_field = "hidden"
+            resolver_type = "getter"
+
+            [[hidden_field]]
+            target_type = "Hidden"
+            field_def = "secret: String"
+
+            [[resolver_rule]]
+            parent_type = "*Connection"
+            resolver_type = "field"
+
+            [[resolver_rule]]
+            parent_type = "User"
+            list = false
+
+            [ignore]
+            object = ["Hidden", "Missing"]
+            "#,
+        )
+        .unwrap();
+
+        let parsed = parse_schema(schema, &config).unwrap();
+        let mut filtered = parsed.clone();
+        crate::parse::ignoring::remove_ignored_from_structure(&mut filtered, &config).unwrap();
+
+        let kinds = validate_config(&config, &parsed, &filtered)
+            .into_iter()
+            .map(|d| (d.kind, d.entry))
+            .collect::<Vec<(DiagnosticKind, String)>>();
+        assert_eq!(
+            kinds,
+            vec![
+                (
+                    DiagnosticKind::UnknownTarget,
+                    r#"[[resolver]] target_type = "User", target_field = "nmae""#.to_string()
+                ),
+                (
+                    DiagnosticKind::NeverApplied,
+                    r#"[[resolver]] target_type = "Query", target_field = "hidden""#.to_string()
+                ),
+                (
+                    DiagnosticKind::InvalidValue,
+                    r#"[[resolver]] target_type = "Query", target_field = "hidden""#.to_string()
+                ),
+                (
+                    DiagnosticKind::UnknownTarget,
+                    r#"[[resolver_rule]] parent_type = "*Connection""#.to_string()
+                ),
+                (
+                    DiagnosticKind::InvalidValue,
+                    r#"[[resolver_rule]] parent_type = "User", list = false"#.to_string()
+                ),
+                (
+                    DiagnosticKind::NeverApplied,
+                    r#"[[hidden_field]] target_type = "Hidden", field_def = "secret: String""#
+                        .to_string()
+                ),
+                (
+                    DiagnosticKind::UnknownTarget,
+                    r#"[ignore] object = "Missing""#.to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn report_snippet_errors_with_their_entry() {
+        let path = std::env::temp_dir().join("report_snippet_errors_with_their_entry.toml");
+        let path_str = path.to_str().unwrap();
+        let load = |config: &str| {
+            std::fs::write(&path, config).unwrap();
+            let config = RendererConfig::load(path_str).unwrap();
+            check_snippets(&config).unwrap_err().to_string()
+        };
+
+        let error = load(
+            r##"
+            [[resolver]]
+            # attribute = "#[graphql(name = \"x\")]"
+            target_type = "Query"
+            target_field = "me"
+            attribute = "#[graphql(name = \"x\")"
+            "##,
+        );
+        assert_eq!(
+            error,
+            format!(
+                r#"invalid attributes in config entry [[resolver]] target_type = "Query", target_field = "me" of {}:6: lex error"#,
+                path_str
+            )
+        );
+
+        let error = load(
+            r#"
+            [[hidden_field]]
+            target_type = "User"
+            field_def = "name: String"
+
+            [[hidden_field]]
+            target_type = "User"
+            field_def = "age: i64,"
+            "#,
+        );
+        assert!(
+            error.contains(r#"[[hidden_field]] target_type = "User", field_def = "age: i64," of "#)
+        );
+        assert!(error.ends_with(":8: unexpected token"), "{}", error);
+
+        let error = load(
+            r#"
+            [[type]]
+            target_type = "User"
+            visible = "crate::is admin"
+            "#,
+        );
+        assert!(
+            error.starts_with(r#"invalid path in config entry [[type]] target_type = "User" of "#)
+        );
+        assert!(error.contains(":4: "), "{}", error);
+    }
+
+    #[test]
+    pub fn parse_snippets_as_their_kind() {
+        assert!(SnippetKind::Use.parse("use std::fs").is_ok());
+        assert!(SnippetKind::Use.parse("use std::fs;").is_err());
+        assert!(SnippetKind::Field.parse("hidden: String").is_ok());
+        assert!(SnippetKind::Field.parse("hidden: String,").is_err());
+        assert!(SnippetKind::Attributes
+            .parse(r#"#[cfg(feature = "x")] #[allow(unused)]"#)
+            .is_ok());
+        assert!(SnippetKind::Derives
+            .parse("Default, serde::Serialize")
+            .is_ok());
+        assert!(SnippetKind::Derives.parse("").is_err());
+        assert!(SnippetKind::Methods
+            .parse("pub async fn a(&self) -> i64 { 1 } pub async fn b(&self) -> i64 { 2 }")
+            .is_ok());
+        assert!(SnippetKind::Methods
+            .parse("pub async fn a(&self) -> i64")
+            .is_err());
+    }
+}
@@ -0,0 +1,33 @@
+use std::process::Command;
+
+#[test]
+fn exit_with_an_error_on_failure() {
+    let output_dir = std::env::temp_dir().join("exit_with_an_error_on_failure");
+    let output = Command::new(env!("CARGO_BIN_EXE_async-graphql-reverse"))
+        .args(["-i", "no_such_schema.graphql", "-o"])
+        .arg(&output_dir)
+        .arg("schema")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("error: failed to access no_such_schema.graphql"));
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn reject_watch_with_init() {
+    let config_path = std::env::temp_dir().join("reject_watch_with_init.toml");
+    let output = Command::new(env!("CARGO_BIN_EXE_async-graphql-reverse"))
+        .args(["-i", "no_such_schema.graphql", "--watch", "-c"])
+        .arg(&config_path)
+        .args(["init", "--force"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("--watch only regenerates the code"));
+    assert!(!config_path.exists());
+}